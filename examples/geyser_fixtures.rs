@@ -0,0 +1,430 @@
+//! Builds the synthetic Geyser fixtures in `tests/fixtures/geyser`
+//!
+//! Each fixture is a prost-encoded `SubscribeUpdate` holding one Pump.fun
+//! create transaction. They are assembled here rather than recorded from
+//! mainnet: signatures, blockhashes, router and lookup table addresses are
+//! placeholders, and the metadata and log lines are made up. Account order,
+//! instruction data and the CreateEvent layout follow the Pump.fun IDL.
+//!
+//! ```bash
+//! cargo run --example geyser_fixtures -- tests/fixtures/geyser
+//! ```
+
+use prost::Message;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::str::FromStr;
+use yellowstone_grpc::proto::geyser::{
+    subscribe_update::UpdateOneof, SubscribeUpdate, SubscribeUpdateTransaction,
+    SubscribeUpdateTransactionInfo,
+};
+use yellowstone_grpc::proto::solana::storage::confirmed_block::{
+    CompiledInstruction, InnerInstruction, InnerInstructions, Message as TransactionMessage,
+    MessageAddressTableLookup, MessageHeader, Transaction, TransactionError, TransactionStatusMeta,
+};
+
+const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+const RENT_SYSVAR_ID: &str = "SysvarRent111111111111111111111111111111111";
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Create instruction discriminator
+const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+
+/// Anchor's tag on self-CPI event instructions
+const EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// CreateEvent discriminator
+const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+
+/// Placeholder program a create is routed through
+const ROUTER: Pubkey = Pubkey::new_from_array([7; 32]);
+
+/// Placeholder address lookup table
+const LOOKUP_TABLE: Pubkey = Pubkey::new_from_array([8; 32]);
+
+fn pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).unwrap()
+}
+
+/// Accounts of one create
+struct CreateKeys {
+    program: Pubkey,
+    mint: Pubkey,
+    creator: Pubkey,
+    token_program: Pubkey,
+    bonding_curve: Pubkey,
+    associated_bonding_curve: Pubkey,
+    metadata: Pubkey,
+    event_authority: Pubkey,
+}
+
+impl CreateKeys {
+    fn new(token_program: &str, mint: &str, creator: &str) -> Self {
+        let program = pubkey(PUMPFUN_PROGRAM_ID);
+        let token_program = pubkey(token_program);
+        let mint = pubkey(mint);
+        let metadata_program = pubkey(METADATA_PROGRAM_ID);
+        let bonding_curve = Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program).0;
+
+        Self {
+            program,
+            mint,
+            creator: pubkey(creator),
+            token_program,
+            bonding_curve,
+            associated_bonding_curve: Pubkey::find_program_address(
+                &[bonding_curve.as_ref(), token_program.as_ref(), mint.as_ref()],
+                &pubkey(ASSOCIATED_TOKEN_PROGRAM_ID),
+            )
+            .0,
+            metadata: Pubkey::find_program_address(
+                &[b"metadata", metadata_program.as_ref(), mint.as_ref()],
+                &metadata_program,
+            )
+            .0,
+            event_authority: Pubkey::find_program_address(&[b"__event_authority"], &program).0,
+        }
+    }
+
+    /// Create instruction accounts, in IDL order
+    fn accounts(&self) -> Vec<Pubkey> {
+        vec![
+            self.mint,
+            Pubkey::find_program_address(&[b"mint-authority"], &self.program).0,
+            self.bonding_curve,
+            self.associated_bonding_curve,
+            Pubkey::find_program_address(&[b"global"], &self.program).0,
+            pubkey(METADATA_PROGRAM_ID),
+            self.metadata,
+            self.creator,
+            solana_sdk::system_program::ID,
+            self.token_program,
+            pubkey(ASSOCIATED_TOKEN_PROGRAM_ID),
+            pubkey(RENT_SYSVAR_ID),
+            self.event_authority,
+            self.program,
+        ]
+    }
+
+    /// Static account keys: fee payer and mint signers first, then every
+    /// create account not in `loaded`, then `extra`
+    fn account_keys(&self, loaded: &[Pubkey], extra: &[Pubkey]) -> Vec<Pubkey> {
+        let mut keys = vec![self.creator, self.mint];
+        for key in self.accounts().into_iter().chain(extra.iter().copied()) {
+            if !keys.contains(&key) && !loaded.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+}
+
+fn push_string(data: &mut Vec<u8>, value: &str) {
+    data.extend((value.len() as u32).to_le_bytes());
+    data.extend(value.as_bytes());
+}
+
+/// Create instruction data: name, symbol, uri, creator
+fn create_data(keys: &CreateKeys, name: &str, symbol: &str, uri: &str) -> Vec<u8> {
+    let mut data = CREATE_DISCRIMINATOR.to_vec();
+    push_string(&mut data, name);
+    push_string(&mut data, symbol);
+    push_string(&mut data, uri);
+    data.extend(keys.creator.to_bytes());
+    data
+}
+
+/// Self-CPI CreateEvent with the initial curve reserves
+fn create_event(keys: &CreateKeys, name: &str, symbol: &str, uri: &str) -> Vec<u8> {
+    let mut data = EVENT_IX_TAG.to_vec();
+    data.extend(CREATE_EVENT_DISCRIMINATOR);
+    push_string(&mut data, name);
+    push_string(&mut data, symbol);
+    push_string(&mut data, uri);
+    for key in [keys.mint, keys.bonding_curve, keys.creator, keys.creator] {
+        data.extend(key.to_bytes());
+    }
+    data.extend(1_730_000_000i64.to_le_bytes());
+    for reserve in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000] {
+        data.extend(reserve.to_le_bytes());
+    }
+    data
+}
+
+/// Log lines of a successful create, optionally invoked by `router`
+fn create_logs(program: &Pubkey, router: Option<&Pubkey>) -> Vec<String> {
+    let mut logs = Vec::new();
+    let depth = match router {
+        Some(router) => {
+            logs.push(format!("Program {} invoke [1]", router));
+            2
+        }
+        None => 1,
+    };
+    logs.push(format!("Program {} invoke [{}]", program, depth));
+    logs.push("Program log: Instruction: Create".to_string());
+    logs.push(format!("Program {} invoke [{}]", program, depth + 1));
+    logs.push(format!("Program {} consumed 2003 of 150000 compute units", program));
+    logs.push(format!("Program {} success", program));
+    logs.push(format!("Program {} consumed 120000 of 200000 compute units", program));
+    logs.push(format!("Program {} success", program));
+    if let Some(router) = router {
+        logs.push(format!("Program {} success", router));
+    }
+    logs
+}
+
+fn index_of(keys: &[Pubkey], key: &Pubkey) -> u8 {
+    keys.iter().position(|candidate| candidate == key).unwrap() as u8
+}
+
+fn indexes(keys: &[Pubkey], accounts: &[Pubkey]) -> Vec<u8> {
+    accounts.iter().map(|account| index_of(keys, account)).collect()
+}
+
+/// One transaction update
+struct Update {
+    /// Every signature byte; fixtures tell apart by it
+    signature_byte: u8,
+    slot: u64,
+    account_keys: Vec<Pubkey>,
+    loaded_writable: Vec<Pubkey>,
+    loaded_readonly: Vec<Pubkey>,
+    instructions: Vec<CompiledInstruction>,
+    inner_instructions: Vec<InnerInstructions>,
+    logs: Vec<String>,
+    err: Option<TransactionError>,
+}
+
+impl Update {
+    fn encode(self) -> Vec<u8> {
+        let signature = vec![self.signature_byte; 64];
+        let versioned = !self.loaded_writable.is_empty() || !self.loaded_readonly.is_empty();
+        let total_accounts = self.account_keys.len() + self.loaded_writable.len() + self.loaded_readonly.len();
+        let address_table_lookups = if versioned {
+            vec![MessageAddressTableLookup {
+                account_key: LOOKUP_TABLE.to_bytes().to_vec(),
+                writable_indexes: (0..self.loaded_writable.len() as u8).collect(),
+                readonly_indexes: (0..self.loaded_readonly.len() as u8).map(|index| index + 10).collect(),
+            }]
+        } else {
+            Vec::new()
+        };
+
+        SubscribeUpdate {
+            filters: vec!["pumpfun".into()],
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                slot: self.slot,
+                transaction: Some(SubscribeUpdateTransactionInfo {
+                    signature: signature.clone(),
+                    is_vote: false,
+                    index: 7,
+                    transaction: Some(Transaction {
+                        signatures: vec![signature],
+                        message: Some(TransactionMessage {
+                            header: Some(MessageHeader {
+                                num_required_signatures: 2,
+                                num_readonly_signed_accounts: 0,
+                                num_readonly_unsigned_accounts: 6,
+                            }),
+                            account_keys: self.account_keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                            recent_blockhash: vec![9; 32],
+                            instructions: self.instructions,
+                            versioned,
+                            address_table_lookups,
+                        }),
+                    }),
+                    meta: Some(TransactionStatusMeta {
+                        err: self.err,
+                        fee: 105_000,
+                        pre_balances: vec![0; total_accounts],
+                        post_balances: vec![0; total_accounts],
+                        inner_instructions: self.inner_instructions,
+                        inner_instructions_none: false,
+                        log_messages: self.logs,
+                        log_messages_none: false,
+                        loaded_writable_addresses: self.loaded_writable.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                        loaded_readonly_addresses: self.loaded_readonly.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                        compute_units_consumed: Some(120_000),
+                    }),
+                }),
+            })),
+        }
+        .encode_to_vec()
+    }
+}
+
+/// Create as an outer instruction after a compute budget instruction;
+/// legacy token program, metadata from the instruction only
+fn create_outer() -> Vec<u8> {
+    let keys = CreateKeys::new(
+        TOKEN_PROGRAM_ID,
+        "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr",
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+    );
+    let compute_budget = pubkey(COMPUTE_BUDGET_PROGRAM_ID);
+    let account_keys = keys.account_keys(&[], &[compute_budget]);
+
+    let set_compute_unit_limit = CompiledInstruction {
+        program_id_index: index_of(&account_keys, &compute_budget) as u32,
+        accounts: Vec::new(),
+        data: vec![2, 0x40, 0x0d, 0x03, 0x00],
+    };
+    let create = CompiledInstruction {
+        program_id_index: index_of(&account_keys, &keys.program) as u32,
+        accounts: indexes(&account_keys, &keys.accounts()),
+        data: create_data(&keys, "Outer Coin", "OUTER", "https://ipfs.io/ipfs/outer"),
+    };
+
+    Update {
+        signature_byte: 1,
+        slot: 290_000_001,
+        logs: create_logs(&keys.program, None),
+        account_keys,
+        loaded_writable: Vec::new(),
+        loaded_readonly: Vec::new(),
+        instructions: vec![set_compute_unit_limit, create],
+        inner_instructions: Vec::new(),
+        err: None,
+    }
+    .encode()
+}
+
+/// Create routed through another program; Token-2022 mint, metadata from
+/// the self-CPI CreateEvent
+fn create_inner_cpi() -> Vec<u8> {
+    let keys = CreateKeys::new(
+        TOKEN_2022_PROGRAM_ID,
+        "3vz82EWYv8xnc7Cm7qSgERcpMeqw92PcX8PBz88npump",
+        "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9",
+    );
+    let account_keys = keys.account_keys(&[], &[ROUTER]);
+    let accounts = indexes(&account_keys, &keys.accounts());
+    let program_id_index = index_of(&account_keys, &keys.program) as u32;
+    let uri = "https://ipfs.io/ipfs/routed";
+
+    let routed = CompiledInstruction {
+        program_id_index: index_of(&account_keys, &ROUTER) as u32,
+        accounts: accounts.clone(),
+        data: vec![1, 2, 3, 4],
+    };
+    let inner = InnerInstructions {
+        index: 0,
+        instructions: vec![
+            InnerInstruction {
+                program_id_index,
+                accounts,
+                data: create_data(&keys, "Routed Coin", "ROUTE", uri),
+                stack_height: Some(2),
+            },
+            InnerInstruction {
+                program_id_index,
+                accounts: vec![index_of(&account_keys, &keys.event_authority)],
+                data: create_event(&keys, "Routed Coin", "ROUTE", uri),
+                stack_height: Some(3),
+            },
+        ],
+    };
+
+    Update {
+        signature_byte: 2,
+        slot: 290_000_002,
+        logs: create_logs(&keys.program, Some(&ROUTER)),
+        account_keys,
+        loaded_writable: Vec::new(),
+        loaded_readonly: Vec::new(),
+        instructions: vec![routed],
+        inner_instructions: vec![inner],
+        err: None,
+    }
+    .encode()
+}
+
+/// Create whose associated bonding curve and token program are loaded
+/// from an address lookup table
+fn create_lookup_table() -> Vec<u8> {
+    let keys = CreateKeys::new(
+        TOKEN_PROGRAM_ID,
+        "BqnpCdDLPV2pFdAaLnVidmn3G93RRvr7aS9ZFpFdpump",
+        "8ExsMZsCnMUhRsgU8LzSDnRSL6WSBezTnq3XzXBQ2S3n",
+    );
+    let loaded_writable = vec![keys.associated_bonding_curve];
+    let loaded_readonly = vec![keys.token_program, pubkey(METADATA_PROGRAM_ID), pubkey(RENT_SYSVAR_ID)];
+    let loaded: Vec<Pubkey> = loaded_writable.iter().chain(&loaded_readonly).copied().collect();
+    let account_keys = keys.account_keys(&loaded, &[]);
+    let resolved: Vec<Pubkey> = account_keys.iter().chain(&loaded).copied().collect();
+
+    let create = CompiledInstruction {
+        program_id_index: index_of(&resolved, &keys.program) as u32,
+        accounts: indexes(&resolved, &keys.accounts()),
+        data: create_data(&keys, "Lookup Coin", "LUT", "https://ipfs.io/ipfs/lookup"),
+    };
+
+    Update {
+        signature_byte: 3,
+        slot: 290_000_003,
+        logs: create_logs(&keys.program, None),
+        account_keys,
+        loaded_writable,
+        loaded_readonly,
+        instructions: vec![create],
+        inner_instructions: Vec::new(),
+        err: None,
+    }
+    .encode()
+}
+
+/// Create that failed with a Pump.fun custom error
+fn create_failed() -> Vec<u8> {
+    let keys = CreateKeys::new(
+        TOKEN_PROGRAM_ID,
+        "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R",
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+    );
+    let account_keys = keys.account_keys(&[], &[]);
+
+    let create = CompiledInstruction {
+        program_id_index: index_of(&account_keys, &keys.program) as u32,
+        accounts: indexes(&account_keys, &keys.accounts()),
+        data: create_data(&keys, "Failed Coin", "FAIL", "https://ipfs.io/ipfs/failed"),
+    };
+
+    // bincode of TransactionError::InstructionError(0, InstructionError::Custom(6000))
+    let mut err = 8u32.to_le_bytes().to_vec();
+    err.push(0);
+    err.extend(25u32.to_le_bytes());
+    err.extend(6000u32.to_le_bytes());
+
+    let mut logs = create_logs(&keys.program, None);
+    logs.truncate(3);
+    logs.push(format!("Program {} failed: custom program error: 0x1770", keys.program));
+
+    Update {
+        signature_byte: 4,
+        slot: 290_000_004,
+        logs,
+        account_keys,
+        loaded_writable: Vec::new(),
+        loaded_readonly: Vec::new(),
+        instructions: vec![create],
+        inner_instructions: Vec::new(),
+        err: Some(TransactionError { err }),
+    }
+    .encode()
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = std::env::args().nth(1).unwrap_or_else(|| "tests/fixtures/geyser".to_string());
+    let dir = Path::new(&dir);
+
+    std::fs::write(dir.join("create_outer.bin"), create_outer())?;
+    std::fs::write(dir.join("create_inner_cpi.bin"), create_inner_cpi())?;
+    std::fs::write(dir.join("create_lookup_table.bin"), create_lookup_table())?;
+    std::fs::write(dir.join("create_failed.bin"), create_failed())?;
+
+    Ok(())
+}
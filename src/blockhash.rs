@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash};
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::config::Config;
+
+/// Cached blockhashes older than this are fetched again on use
+const MAX_CACHE_AGE: Duration = Duration::from_secs(5);
+
+/// A blockhash to sign with and the last block height it is valid at
+#[derive(Debug, Clone, Copy)]
+pub struct RecentBlockhash {
+    pub hash: Hash,
+    pub last_valid_block_height: u64,
+    fetched_at: Instant,
+}
+
+/// Latest blockhash, kept fresh by a background task
+///
+/// Signing takes the cached blockhash instead of fetching one per
+/// transaction; a stale or missing entry is fetched on demand.
+pub struct BlockhashCache {
+    refresh_interval: Duration,
+    latest: Mutex<Option<RecentBlockhash>>,
+}
+
+impl BlockhashCache {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            refresh_interval: Duration::from_millis(config.blockhash_refresh_ms),
+            latest: Mutex::new(None),
+        }
+    }
+
+    /// Start background refreshing
+    pub fn start(self: &Arc<Self>, rpc_url: String) {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            let client = RpcClient::new(rpc_url);
+            loop {
+                if let Err(e) = cache.refresh(&client).await {
+                    log::warn!("Failed to refresh blockhash: {:#}", e);
+                }
+                sleep(cache.refresh_interval).await;
+            }
+        });
+    }
+
+    /// Latest blockhash, from the cache when it is fresh enough
+    pub async fn get(&self, client: &RpcClient) -> Result<RecentBlockhash> {
+        let cached = *self.latest.lock().unwrap();
+        if let Some(cached) = cached {
+            if cached.fetched_at.elapsed() <= MAX_CACHE_AGE {
+                return Ok(cached);
+            }
+        }
+
+        self.refresh(client).await
+    }
+
+    /// Fetch the latest blockhash and cache it
+    async fn refresh(&self, client: &RpcClient) -> Result<RecentBlockhash> {
+        let (hash, last_valid_block_height) = client
+            .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+            .await
+            .context("Failed to get latest blockhash")?;
+
+        let blockhash = RecentBlockhash {
+            hash,
+            last_valid_block_height,
+            fetched_at: Instant::now(),
+        };
+        *self.latest.lock().unwrap() = Some(blockhash);
+        Ok(blockhash)
+    }
+}
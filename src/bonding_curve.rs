@@ -0,0 +1,487 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::{BONDING_CURVE_DISCRIMINATOR, FEE_CONFIG_DISCRIMINATOR, GLOBAL_DISCRIMINATOR};
+use crate::utils::{ceil_bps, BorshReader, BPS_DENOMINATOR};
+
+/// Pump.fun `Global` config account
+#[derive(Debug, Clone)]
+pub struct Global {
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+}
+
+impl Global {
+    /// Deserialize the account data, including its discriminator
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&GLOBAL_DISCRIMINATOR)
+            .context("Account is not a Pump.fun Global account")?;
+        let mut reader = BorshReader::new(payload);
+
+        let initialized = reader.read_bool().context("Global: initialized")?;
+        let authority = reader.read_pubkey().context("Global: authority")?;
+        let fee_recipient = reader.read_pubkey().context("Global: fee_recipient")?;
+        let initial_virtual_token_reserves = reader.read_u64().context("Global: initial_virtual_token_reserves")?;
+        let initial_virtual_sol_reserves = reader.read_u64().context("Global: initial_virtual_sol_reserves")?;
+        let initial_real_token_reserves = reader.read_u64().context("Global: initial_real_token_reserves")?;
+        let token_total_supply = reader.read_u64().context("Global: token_total_supply")?;
+        let fee_basis_points = reader.read_u64().context("Global: fee_basis_points")?;
+
+        // withdraw_authority, enable_migrate and pool_migration_fee precede the
+        // creator fee; accounts from before creator fees end here
+        let creator_fee_basis_points = (|| {
+            reader.read_pubkey()?;
+            reader.read_bool()?;
+            reader.read_u64()?;
+            reader.read_u64()
+        })()
+        .unwrap_or(0);
+
+        Ok(Self {
+            initialized,
+            authority,
+            fee_recipient,
+            initial_virtual_token_reserves,
+            initial_virtual_sol_reserves,
+            initial_real_token_reserves,
+            token_total_supply,
+            fee_basis_points,
+            creator_fee_basis_points,
+        })
+    }
+
+    /// Fetch and decode the Global account
+    pub async fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = rpc_client
+            .get_account_data(address)
+            .await
+            .context("Failed to fetch Pump.fun Global account")?;
+
+        Self::decode(&data)
+    }
+}
+
+/// Fee rates charged on a trade (bps)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    pub lp_fee_bps: u64,
+    pub protocol_fee_bps: u64,
+    pub creator_fee_bps: u64,
+}
+
+/// Fees that apply from a market cap upwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTier {
+    pub market_cap_lamports_threshold: u128,
+    pub fees: Fees,
+}
+
+/// Fee program `FeeConfig` account for the Pump.fun program
+///
+/// Supersedes the fee rates in `Global`: each trade pays the tier its
+/// curve's market cap has reached.
+#[derive(Debug, Clone)]
+pub struct FeeConfig {
+    pub flat_fees: Fees,
+    /// Ascending by threshold
+    pub fee_tiers: Vec<FeeTier>,
+}
+
+impl Fees {
+    fn read(reader: &mut BorshReader) -> Option<Self> {
+        Some(Self {
+            lp_fee_bps: reader.read_u64()?,
+            protocol_fee_bps: reader.read_u64()?,
+            creator_fee_bps: reader.read_u64()?,
+        })
+    }
+}
+
+impl FeeConfig {
+    /// Deserialize the account data, including its discriminator
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&FEE_CONFIG_DISCRIMINATOR)
+            .context("Account is not a fee program FeeConfig account")?;
+        let mut reader = BorshReader::new(payload);
+
+        reader.read_u8().context("FeeConfig: bump")?;
+        reader.read_pubkey().context("FeeConfig: admin")?;
+        let flat_fees = Fees::read(&mut reader).context("FeeConfig: flat_fees")?;
+        let tier_count = reader.read_u32().context("FeeConfig: fee_tiers")?;
+        let fee_tiers = (0..tier_count)
+            .map(|_| {
+                Some(FeeTier {
+                    market_cap_lamports_threshold: reader.read_u128()?,
+                    fees: Fees::read(&mut reader)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .context("FeeConfig: fee_tiers")?;
+
+        Ok(Self { flat_fees, fee_tiers })
+    }
+
+    /// Fetch and decode the FeeConfig account
+    pub async fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = rpc_client
+            .get_account_data(address)
+            .await
+            .context("Failed to fetch Pump.fun FeeConfig account")?;
+
+        Self::decode(&data)
+    }
+
+    /// Fees at a market cap: the highest tier reached, or the lowest tier
+    /// below every threshold
+    pub fn fees_at(&self, market_cap_lamports: u128) -> Fees {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| market_cap_lamports >= tier.market_cap_lamports_threshold)
+            .or_else(|| self.fee_tiers.first())
+            .map_or(self.flat_fees, |tier| tier.fees)
+    }
+}
+
+/// Pump.fun `BondingCurve` account
+#[derive(Debug, Clone)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+    /// Default pubkey on curves created before creator fees existed
+    pub creator: Pubkey,
+}
+
+/// Cost breakdown of a buy
+#[derive(Debug, Clone, Copy)]
+pub struct BuyQuote {
+    /// Tokens received
+    pub tokens: u64,
+    /// SOL paid into the curve, before fees (lamports)
+    pub sol_cost: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    /// Everything the buyer pays (lamports)
+    pub total_sol: u64,
+}
+
+/// Proceeds breakdown of a sell
+#[derive(Debug, Clone, Copy)]
+pub struct SellQuote {
+    /// Tokens sold
+    pub tokens: u64,
+    /// SOL released by the curve, before fees (lamports)
+    pub sol_out: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    /// What the seller receives (lamports)
+    pub net_sol: u64,
+}
+
+impl BondingCurve {
+    /// Deserialize the account data, including its discriminator
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&BONDING_CURVE_DISCRIMINATOR)
+            .context("Account is not a Pump.fun bonding curve")?;
+        let mut reader = BorshReader::new(payload);
+
+        Ok(Self {
+            virtual_token_reserves: reader.read_u64().context("BondingCurve: virtual_token_reserves")?,
+            virtual_sol_reserves: reader.read_u64().context("BondingCurve: virtual_sol_reserves")?,
+            real_token_reserves: reader.read_u64().context("BondingCurve: real_token_reserves")?,
+            real_sol_reserves: reader.read_u64().context("BondingCurve: real_sol_reserves")?,
+            token_total_supply: reader.read_u64().context("BondingCurve: token_total_supply")?,
+            complete: reader.read_bool().context("BondingCurve: complete")?,
+            creator: reader.read_pubkey().unwrap_or_default(),
+        })
+    }
+
+    /// Fetch and decode a bonding curve account
+    pub async fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = rpc_client
+            .get_account_data(address)
+            .await
+            .with_context(|| format!("Failed to fetch bonding curve {}", address))?;
+
+        Self::decode(&data)
+    }
+
+    /// Curve state right after creation, before any buys
+    pub fn initial(global: &Global, creator: Pubkey) -> Self {
+        Self {
+            virtual_token_reserves: global.initial_virtual_token_reserves,
+            virtual_sol_reserves: global.initial_virtual_sol_reserves,
+            real_token_reserves: global.initial_real_token_reserves,
+            real_sol_reserves: 0,
+            token_total_supply: global.token_total_supply,
+            complete: false,
+            creator,
+        }
+    }
+
+    /// Spot price in lamports per raw token unit
+    pub fn price(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        self.virtual_sol_reserves as f64 / self.virtual_token_reserves as f64
+    }
+
+    /// Share of the curve's sellable tokens already bought, in percent
+    pub fn progress(&self, global: &Global) -> f64 {
+        if self.complete {
+            return 100.0;
+        }
+        if global.initial_real_token_reserves == 0 {
+            return 0.0;
+        }
+
+        let sold = global.initial_real_token_reserves.saturating_sub(self.real_token_reserves);
+        sold as f64 * 100.0 / global.initial_real_token_reserves as f64
+    }
+
+    /// Market cap implied by the curve's price (lamports)
+    pub fn market_cap(&self) -> u128 {
+        if self.virtual_token_reserves == 0 {
+            return 0;
+        }
+        self.virtual_sol_reserves as u128 * self.token_total_supply as u128 / self.virtual_token_reserves as u128
+    }
+
+    /// Fee rates a trade against the curve pays right now
+    ///
+    /// The fee config tier for the curve's market cap when there is one,
+    /// the flat `Global` rates otherwise. Curves without a creator pay no
+    /// creator fee.
+    pub fn fees(&self, global: &Global, fee_config: Option<&FeeConfig>) -> Fees {
+        let mut fees = match fee_config {
+            Some(fee_config) => fee_config.fees_at(self.market_cap()),
+            None => Fees {
+                lp_fee_bps: 0,
+                protocol_fee_bps: global.fee_basis_points,
+                creator_fee_bps: global.creator_fee_basis_points,
+            },
+        };
+        if self.creator == Pubkey::default() {
+            fees.creator_fee_bps = 0;
+        }
+        fees
+    }
+
+    /// Tokens a buy spending at most `sol` lamports (fees included) gets
+    pub fn quote_buy_by_sol(&self, global: &Global, fee_config: Option<&FeeConfig>, sol: u64) -> BuyQuote {
+        if sol == 0 || self.complete {
+            return self.quote_buy_by_tokens(global, fee_config, 0);
+        }
+
+        let fees = self.fees(global, fee_config);
+        let fee_bps = (fees.protocol_fee_bps + fees.creator_fee_bps) as u128;
+
+        // Price the rounded token amount exactly as the program will; fee
+        // rounding can push the total over budget, so shrink the budget by
+        // the overshoot until it fits
+        let mut budget = sol;
+        loop {
+            let input = budget as u128 * BPS_DENOMINATOR / (BPS_DENOMINATOR + fee_bps);
+            let tokens = input * self.virtual_token_reserves as u128 / (self.virtual_sol_reserves as u128 + input);
+            let quote = self.quote_buy_by_tokens(global, fee_config, tokens as u64);
+
+            if quote.total_sol <= sol || budget == 0 {
+                return quote;
+            }
+            budget = budget.saturating_sub(quote.total_sol - sol);
+        }
+    }
+
+    /// SOL cost of buying exactly `tokens`, matching the program's rounding
+    pub fn quote_buy_by_tokens(&self, global: &Global, fee_config: Option<&FeeConfig>, tokens: u64) -> BuyQuote {
+        let tokens = tokens.min(self.real_token_reserves);
+        if tokens == 0 || self.complete {
+            return BuyQuote {
+                tokens: 0,
+                sol_cost: 0,
+                protocol_fee: 0,
+                creator_fee: 0,
+                total_sol: 0,
+            };
+        }
+
+        let sol_cost = (tokens as u128 * self.virtual_sol_reserves as u128
+            / (self.virtual_token_reserves as u128 - tokens as u128)
+            + 1) as u64;
+        let fees = self.fees(global, fee_config);
+        let protocol_fee = ceil_bps(sol_cost, fees.protocol_fee_bps);
+        let creator_fee = ceil_bps(sol_cost, fees.creator_fee_bps);
+
+        BuyQuote {
+            tokens,
+            sol_cost,
+            protocol_fee,
+            creator_fee,
+            total_sol: sol_cost + protocol_fee + creator_fee,
+        }
+    }
+
+    /// SOL received for selling `tokens`, after fees
+    pub fn quote_sell(&self, global: &Global, fee_config: Option<&FeeConfig>, tokens: u64) -> SellQuote {
+        if tokens == 0 || self.complete {
+            return SellQuote {
+                tokens: 0,
+                sol_out: 0,
+                protocol_fee: 0,
+                creator_fee: 0,
+                net_sol: 0,
+            };
+        }
+
+        let sol_out = (tokens as u128 * self.virtual_sol_reserves as u128
+            / (self.virtual_token_reserves as u128 + tokens as u128)) as u64;
+        let fees = self.fees(global, fee_config);
+        let protocol_fee = ceil_bps(sol_out, fees.protocol_fee_bps);
+        let creator_fee = ceil_bps(sol_out, fees.creator_fee_bps);
+
+        SellQuote {
+            tokens,
+            sol_out,
+            protocol_fee,
+            creator_fee,
+            net_sol: sol_out.saturating_sub(protocol_fee + creator_fee),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAMPORTS_PER_SOL: u128 = 1_000_000_000;
+
+    /// Mainnet Global values
+    fn global() -> Global {
+        Global {
+            initialized: true,
+            authority: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            fee_basis_points: 95,
+            creator_fee_basis_points: 5,
+        }
+    }
+
+    fn fees(protocol_fee_bps: u64, creator_fee_bps: u64) -> Fees {
+        Fees {
+            lp_fee_bps: 0,
+            protocol_fee_bps,
+            creator_fee_bps,
+        }
+    }
+
+    fn tiered_fee_config() -> FeeConfig {
+        FeeConfig {
+            flat_fees: fees(95, 5),
+            fee_tiers: vec![
+                FeeTier {
+                    market_cap_lamports_threshold: 50 * LAMPORTS_PER_SOL,
+                    fees: fees(93, 30),
+                },
+                FeeTier {
+                    market_cap_lamports_threshold: 100 * LAMPORTS_PER_SOL,
+                    fees: fees(93, 5),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn quotes_a_fresh_curve_like_the_program() {
+        let global = global();
+        let curve = BondingCurve::initial(&global, Pubkey::new_unique());
+
+        // 1 SOL into a fresh curve buys 34,612,903.225806 tokens
+        let quote = curve.quote_buy_by_tokens(&global, None, 34_612_903_225_806);
+        assert_eq!(quote.sol_cost, 1_000_000_000);
+        assert_eq!(quote.protocol_fee, 9_500_000);
+        assert_eq!(quote.creator_fee, 500_000);
+        assert_eq!(quote.total_sol, 1_010_000_000);
+
+        let by_sol = curve.quote_buy_by_sol(&global, None, 1_010_000_000);
+        assert_eq!(by_sol.tokens, 34_612_903_225_806);
+        assert_eq!(by_sol.total_sol, 1_010_000_000);
+    }
+
+    #[test]
+    fn charges_the_fee_tier_for_the_curve_market_cap() {
+        let global = global();
+        let fee_config = tiered_fee_config();
+        let curve = BondingCurve::initial(&global, Pubkey::new_unique());
+
+        // A fresh curve (~27.96 SOL) is below every threshold: first tier
+        assert_eq!(curve.market_cap(), 27_958_993_476);
+        assert_eq!(curve.fees(&global, Some(&fee_config)), fees(93, 30));
+
+        let quote = curve.quote_buy_by_tokens(&global, Some(&fee_config), 34_612_903_225_806);
+        assert_eq!(quote.protocol_fee, 9_300_000);
+        assert_eq!(quote.creator_fee, 3_000_000);
+        assert_eq!(quote.total_sol, 1_012_300_000);
+
+        // The same budget buys less once the tier's fees are counted
+        let by_sol = curve.quote_buy_by_sol(&global, Some(&fee_config), 1_010_000_000);
+        assert!(by_sol.tokens < 34_612_903_225_806);
+        assert!(by_sol.total_sol <= 1_010_000_000);
+
+        // 120 SOL market cap reaches the second tier
+        let grown = BondingCurve {
+            virtual_token_reserves: 500_000_000_000_000,
+            virtual_sol_reserves: 60_000_000_000,
+            ..curve.clone()
+        };
+        assert_eq!(grown.fees(&global, Some(&fee_config)), fees(93, 5));
+
+        // Curves from before creator fees pay none in any tier
+        let creatorless = BondingCurve {
+            creator: Pubkey::default(),
+            ..curve
+        };
+        assert_eq!(creatorless.fees(&global, Some(&fee_config)), fees(93, 0));
+    }
+
+    #[test]
+    fn decodes_fee_config_account() {
+        let mut data = FEE_CONFIG_DISCRIMINATOR.to_vec();
+        data.push(255);
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        for bps in [0u64, 95, 5] {
+            data.extend_from_slice(&bps.to_le_bytes());
+        }
+        data.extend_from_slice(&2u32.to_le_bytes());
+        for tier in tiered_fee_config().fee_tiers {
+            data.extend_from_slice(&tier.market_cap_lamports_threshold.to_le_bytes());
+            for bps in [tier.fees.lp_fee_bps, tier.fees.protocol_fee_bps, tier.fees.creator_fee_bps] {
+                data.extend_from_slice(&bps.to_le_bytes());
+            }
+        }
+
+        let fee_config = FeeConfig::decode(&data).unwrap();
+        assert_eq!(fee_config.flat_fees, fees(95, 5));
+        assert_eq!(fee_config.fee_tiers, tiered_fee_config().fee_tiers);
+        assert!(FeeConfig::decode(&data[..data.len() - 1]).is_err());
+    }
+}
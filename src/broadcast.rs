@@ -0,0 +1,338 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentLevel, instruction::Instruction, pubkey::Pubkey,
+    signature::Signature, transaction::VersionedTransaction,
+};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::config::Config;
+use crate::jito::{self, JitoClient};
+
+/// Name Jito bundles are counted under
+const JITO_ENDPOINT: &str = "jito";
+
+/// Bundle status checks after a landing before it is put down to an RPC
+const BUNDLE_STATUS_ATTEMPTS: u32 = 5;
+
+/// Delay between bundle status checks
+const BUNDLE_STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Send and landing counters for one endpoint
+#[derive(Debug, Clone, Default)]
+pub struct EndpointStats {
+    /// Transactions sent
+    pub sent: u64,
+    /// Sends the endpoint accepted
+    pub accepted: u64,
+    /// Landed transactions this RPC was first to accept; every RPC sends the
+    /// same signed copy, so which one carried it on-chain is unknown
+    pub first_accepted_landed: u64,
+    /// Bundles the block engine reports landed in the transaction's slot
+    /// (Jito only)
+    pub landed: u64,
+    total_accept_latency: Duration,
+}
+
+impl EndpointStats {
+    /// Share of sent bundles that landed (Jito only)
+    pub fn landing_rate(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            self.landed as f64 / self.sent as f64
+        }
+    }
+
+    /// Average time the endpoint took to accept a transaction
+    pub fn average_accept_latency(&self) -> Duration {
+        if self.accepted == 0 {
+            Duration::ZERO
+        } else {
+            self.total_accept_latency / self.accepted as u32
+        }
+    }
+}
+
+/// An RPC endpoint transactions are sent to
+struct RpcEndpoint {
+    client: RpcClient,
+    /// Run preflight checks; only the primary RPC does, so its errors can be decoded
+    preflight: bool,
+}
+
+/// A transaction fanned out by the [`Broadcaster`]
+pub struct Broadcast {
+    pub signature: Signature,
+    /// Endpoints that accepted the transaction, in the order they did
+    accepted: Arc<Mutex<Vec<usize>>>,
+    /// Bundle carrying the transaction, once Jito accepted it
+    bundle_id: Arc<Mutex<Option<String>>>,
+}
+
+/// Sends each signed transaction to every endpoint at once
+///
+/// The primary RPC, every `SEND_RPC_URLS` endpoint and, when enabled, the
+/// Jito block engine receive the same signed transaction, so it lands at
+/// most once whichever path gets it in first. Endpoints are indexed RPCs
+/// first, then Jito.
+pub struct Broadcaster {
+    endpoints: Vec<Arc<RpcEndpoint>>,
+    jito: Option<Arc<JitoClient>>,
+    names: Vec<String>,
+    stats: Arc<Mutex<Vec<EndpointStats>>>,
+}
+
+impl Broadcaster {
+    pub fn new(config: &Config) -> Self {
+        let urls = std::iter::once(&config.rpc_url).chain(config.send_rpc_urls.iter());
+        let mut names: Vec<String> = urls.clone().map(String::as_str).map(endpoint_name).collect();
+
+        let endpoints = urls
+            .enumerate()
+            .map(|(index, url)| {
+                Arc::new(RpcEndpoint {
+                    client: RpcClient::new(url.clone()),
+                    preflight: index == 0,
+                })
+            })
+            .collect();
+
+        let jito = config.jito_enabled.then(|| {
+            names.push(JITO_ENDPOINT.to_string());
+            Arc::new(JitoClient::new(
+                config
+                    .jito_block_engine_url
+                    .as_deref()
+                    .unwrap_or(jito::DEFAULT_BLOCK_ENGINE_URL),
+                config.jito_tip_lamports,
+            ))
+        });
+
+        Self {
+            endpoints,
+            jito,
+            stats: Arc::new(Mutex::new(vec![EndpointStats::default(); names.len()])),
+            names,
+        }
+    }
+
+    /// Jito tip to include in every transaction, when Jito is enabled
+    pub fn tip_instruction(&self, payer: &Pubkey) -> Option<Instruction> {
+        self.jito.as_ref().map(|jito| jito.tip_instruction(payer))
+    }
+
+    /// Send a signed transaction to every endpoint
+    ///
+    /// Returns as soon as one endpoint accepts it; slower endpoints finish in
+    /// the background. When all of them reject it, the error to return is
+    /// preferably one carrying a decodable transaction error.
+    pub async fn send(&self, transaction: &VersionedTransaction) -> Result<Broadcast, ClientError> {
+        let broadcast = Broadcast {
+            signature: transaction.signatures[0],
+            accepted: Arc::new(Mutex::new(Vec::new())),
+            bundle_id: Arc::new(Mutex::new(None)),
+        };
+        let (results_tx, mut results) = mpsc::unbounded_channel();
+
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let endpoint = endpoint.clone();
+            let transaction = transaction.clone();
+            let name = self.names[index].clone();
+            let stats = self.stats.clone();
+            let accepted = broadcast.accepted.clone();
+            let results_tx = results_tx.clone();
+
+            tokio::spawn(async move {
+                let config = RpcSendTransactionConfig {
+                    skip_preflight: !endpoint.preflight,
+                    preflight_commitment: Some(CommitmentLevel::Processed),
+                    ..Default::default()
+                };
+                let started = Instant::now();
+                let result = endpoint
+                    .client
+                    .send_transaction_with_config(&transaction, config)
+                    .await
+                    .map(|_| ());
+
+                record_send(&stats, &accepted, index, started, result.is_ok());
+                if let Err(ref e) = result {
+                    log::debug!("{} rejected transaction: {}", name, e);
+                }
+                let _ = results_tx.send(result);
+            });
+        }
+
+        if let Some(jito) = &self.jito {
+            let jito = jito.clone();
+            let transaction = transaction.clone();
+            let index = self.endpoints.len();
+            let stats = self.stats.clone();
+            let accepted = broadcast.accepted.clone();
+            let bundle_id = broadcast.bundle_id.clone();
+            let results_tx = results_tx.clone();
+
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let result = jito.send_bundle(&[transaction]).await;
+
+                record_send(&stats, &accepted, index, started, result.is_ok());
+                let result = match result {
+                    Ok(id) => {
+                        log::info!("Jito bundle submitted: {}", id);
+                        *bundle_id.lock().unwrap() = Some(id);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        log::debug!("Jito rejected bundle: {:#}", e);
+                        Err(ClientErrorKind::Custom(format!("Jito: {:#}", e)).into())
+                    }
+                };
+                let _ = results_tx.send(result);
+            });
+        }
+        drop(results_tx);
+
+        let mut error: Option<ClientError> = None;
+        while let Some(result) = results.recv().await {
+            match result {
+                Ok(()) => return Ok(broadcast),
+                Err(e) => {
+                    if error.is_none() || e.get_transaction_error().is_some() {
+                        error = Some(e);
+                    }
+                }
+            }
+        }
+
+        Err(error.unwrap_or_else(|| ClientErrorKind::Custom("No send endpoints".to_string()).into()))
+    }
+
+    /// Attribute a broadcast that landed in `slot`
+    ///
+    /// Jito is credited when the block engine reports the bundle landed in
+    /// that same slot; its status can trail the confirmation, so it is
+    /// polled for a few seconds in the background. Otherwise one of the RPC
+    /// copies landed, and the RPC that accepted first is counted as such.
+    pub fn record_landing(&self, broadcast: &Broadcast, slot: u64) {
+        let bundle_id = broadcast.bundle_id.lock().unwrap().clone();
+        let first_accepted = broadcast
+            .accepted
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .find(|&index| index < self.endpoints.len());
+        let signature = broadcast.signature;
+        let jito = self.jito.clone();
+        let jito_index = self.endpoints.len();
+        let names = self.names.clone();
+        let stats = self.stats.clone();
+
+        tokio::spawn(async move {
+            let mut bundle_landed = false;
+            if let (Some(jito), Some(bundle_id)) = (jito, bundle_id) {
+                for attempt in 1..=BUNDLE_STATUS_ATTEMPTS {
+                    match jito.bundle_landed(&bundle_id).await {
+                        Ok(Some(bundle_slot)) => {
+                            bundle_landed = bundle_slot == slot;
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(e) => log::debug!("Failed to check Jito bundle {}: {:#}", bundle_id, e),
+                    }
+                    if attempt < BUNDLE_STATUS_ATTEMPTS {
+                        sleep(BUNDLE_STATUS_INTERVAL).await;
+                    }
+                }
+            }
+
+            if bundle_landed {
+                stats.lock().unwrap()[jito_index].landed += 1;
+                log::info!("Transaction {} landed in slot {} via {}", signature, slot, names[jito_index]);
+            } else if let Some(index) = first_accepted {
+                stats.lock().unwrap()[index].first_accepted_landed += 1;
+                log::info!(
+                    "Transaction {} landed in slot {} through RPC; {} accepted it first",
+                    signature,
+                    slot,
+                    names[index]
+                );
+            }
+            log_stats(&names, &stats.lock().unwrap(), jito_index);
+        });
+    }
+
+    /// Statistics per endpoint, by endpoint name
+    pub fn stats(&self) -> Vec<(String, EndpointStats)> {
+        self.names
+            .iter()
+            .cloned()
+            .zip(self.stats.lock().unwrap().iter().cloned())
+            .collect()
+    }
+
+    /// Log send and landing statistics for every endpoint
+    pub fn log_stats(&self) {
+        let (names, stats): (Vec<_>, Vec<_>) = self.stats().into_iter().unzip();
+        log_stats(&names, &stats, self.endpoints.len());
+    }
+}
+
+/// Log a line per endpoint; the first `rpc_endpoints` are RPCs, any other is Jito
+fn log_stats(names: &[String], stats: &[EndpointStats], rpc_endpoints: usize) {
+    log::info!("Send endpoint statistics:");
+    for (index, (name, stats)) in names.iter().zip(stats).enumerate() {
+        if index < rpc_endpoints {
+            log::info!(
+                "  {}: {}/{} accepted, avg accept {:?}, first to accept {} landed",
+                name,
+                stats.accepted,
+                stats.sent,
+                stats.average_accept_latency(),
+                stats.first_accepted_landed
+            );
+        } else {
+            log::info!(
+                "  {}: {}/{} landed ({:.1}%), {} accepted, avg accept {:?}",
+                name,
+                stats.landed,
+                stats.sent,
+                stats.landing_rate() * 100.0,
+                stats.accepted,
+                stats.average_accept_latency()
+            );
+        }
+    }
+}
+
+/// Count a send and, when accepted, its latency and place in the order
+fn record_send(
+    stats: &Mutex<Vec<EndpointStats>>,
+    accepted_order: &Mutex<Vec<usize>>,
+    index: usize,
+    started: Instant,
+    accepted: bool,
+) {
+    let mut stats = stats.lock().unwrap();
+    let endpoint = &mut stats[index];
+    endpoint.sent += 1;
+
+    if accepted {
+        endpoint.accepted += 1;
+        endpoint.total_accept_latency += started.elapsed();
+        accepted_order.lock().unwrap().push(index);
+    }
+}
+
+/// Endpoint host for logs, leaving out paths and query strings that may hold API keys
+fn endpoint_name(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
@@ -1,27 +1,106 @@
 use serde::{Deserialize, Serialize};
+use solana_sdk::{commitment_config::CommitmentLevel, pubkey::Pubkey};
 use std::str::FromStr;
 
+use crate::exit_strategy::{self, ExitStrategy};
+
 /// Pump.fun program ID
 pub const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
+/// Pump.fun fee program ID (owns the fee tier config)
+pub const PUMPFUN_FEE_PROGRAM_ID: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+
+/// PumpSwap AMM program ID (where completed curves migrate)
+pub const PUMP_AMM_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+
 /// Create instruction discriminator for Pump.fun
 pub const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
 
+/// Account discriminator for Pump.fun bonding curve accounts
+pub const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Account discriminator for the Pump.fun Global config account
+pub const GLOBAL_DISCRIMINATOR: [u8; 8] = [167, 232, 232, 177, 200, 108, 114, 127];
+
+/// Account discriminator for the fee program's FeeConfig account
+pub const FEE_CONFIG_DISCRIMINATOR: [u8; 8] = [143, 52, 146, 187, 219, 123, 76, 155];
+
+/// Account discriminator for PumpSwap pools
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Account discriminator for the PumpSwap GlobalConfig account
+pub const AMM_GLOBAL_CONFIG_DISCRIMINATOR: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+
+/// How the priority fee is chosen for each transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFeeStrategy {
+    /// Always use `priority_fee_micro_lamports`
+    Fixed,
+    /// Percentile of recent fees, capped
+    Percentile,
+    /// Percentile of recent fees times a multiplier, capped
+    PercentileMultiplier,
+}
+
+impl FromStr for PriorityFeeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fixed" => Ok(Self::Fixed),
+            "percentile" => Ok(Self::Percentile),
+            "percentile_multiplier" => Ok(Self::PercentileMultiplier),
+            other => Err(format!(
+                "unknown strategy '{}' (expected fixed, percentile or percentile_multiplier)",
+                other
+            )),
+        }
+    }
+}
+
 /// Main configuration for the sniper bot
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Solana RPC endpoint URL
     pub rpc_url: String,
+    /// Solana WebSocket endpoint URL (derived from `rpc_url` if unset)
+    pub ws_url: Option<String>,
     /// Yellowstone Geyser gRPC endpoint (optional)
     pub yellowstone_grpc_url: Option<String>,
+    /// Additional Geyser endpoints, used when racing detection sources
+    pub secondary_grpc_urls: Vec<String>,
+    /// Run all detection sources concurrently instead of falling back
+    pub race_detection: bool,
+    /// Commitment level for the Geyser subscription
+    pub geyser_commitment: CommitmentLevel,
+    /// Accounts a transaction must touch at least one of (defaults to the Pump.fun program)
+    pub geyser_account_include: Vec<String>,
+    /// Transactions touching any of these accounts are dropped
+    pub geyser_account_exclude: Vec<String>,
+    /// Accounts a transaction must touch all of
+    pub geyser_account_required: Vec<String>,
+    /// Also subscribe to bonding curve accounts owned by the program
+    pub geyser_subscribe_bonding_curves: bool,
+    /// Upper bound for the Geyser reconnect backoff (ms)
+    pub geyser_max_reconnect_delay_ms: u64,
     /// Wallet private key (base58 encoded)
     pub private_key: Option<String>,
     /// Wallet mnemonic phrase (alternative to private_key)
     pub mnemonic: Option<String>,
     /// Buy amount in SOL
     pub buy_amount_sol: f64,
-    /// Priority fee in micro-lamports
+    /// Priority fee in micro-lamports (fixed fee, or fallback until fees are sampled)
     pub priority_fee_micro_lamports: u64,
+    /// Priority fee strategy
+    pub priority_fee_strategy: PriorityFeeStrategy,
+    /// Percentile of recent fees to pay (0-100)
+    pub priority_fee_percentile: u8,
+    /// Multiplier applied to the percentile fee
+    pub priority_fee_multiplier: f64,
+    /// Maximum priority fee in micro-lamports
+    pub priority_fee_cap_micro_lamports: u64,
+    /// Interval between recent fee samples (ms)
+    pub priority_fee_refresh_ms: u64,
     /// Minimum initial liquidity (in SOL) to snipe
     pub min_initial_liquidity_sol: f64,
     /// Maximum initial liquidity (in SOL) to snipe
@@ -36,25 +115,82 @@ pub struct Config {
     pub jito_tip_lamports: u64,
     /// Jito block engine URL
     pub jito_block_engine_url: Option<String>,
+    /// Extra endpoints every transaction is also sent to
+    pub send_rpc_urls: Vec<String>,
     /// Maximum compute units for transactions
     pub max_compute_units: u32,
+    /// Commitment a sent transaction must reach to count as confirmed
+    pub confirm_commitment: CommitmentLevel,
+    /// Interval between background blockhash refreshes (ms)
+    pub blockhash_refresh_ms: u64,
+    /// Priority fee increase for each re-signed retry (percent)
+    pub retry_fee_bump_percent: u64,
+    /// Simulate each transaction before sending it
+    pub simulate_transactions: bool,
+    /// Headroom added to simulated compute units when setting the limit (percent)
+    pub compute_unit_margin_percent: u64,
     /// Slippage tolerance in basis points
     pub slippage_bps: u16,
     /// Use WebSocket fallback if gRPC unavailable
     pub use_websocket_fallback: bool,
-    /// Rate limit delay between RPC calls (ms)
+    /// Use RPC polling if WebSocket logs are unavailable
+    pub use_polling_fallback: bool,
+    /// Delay between polls of the RPC polling detector (ms)
     pub rate_limit_ms: u64,
+    /// Snipes evaluated or executed at the same time
+    pub max_in_flight_snipes: usize,
+    /// Detected creates waiting longer than this are dropped (ms, 0 keeps all)
+    ///
+    /// Measured on the local clock from when the detector decoded the create,
+    /// so time the create spent reaching the detector is not counted.
+    pub max_event_age_ms: u64,
+    /// Detected creates buffered before the detector waits for the snipe pipeline
+    pub event_queue_capacity: usize,
+    /// File the buy ledger is persisted to
+    pub ledger_path: String,
+    /// Buys allowed per mint (0 for no limit)
+    pub max_buys_per_mint: u32,
+    /// Buys allowed per creator (0 for no limit)
+    pub max_buys_per_creator: u32,
+    /// Hours a mint or creator stays in the buy ledger after its last buy (0 keeps them forever)
+    pub ledger_retention_hours: u64,
+    /// Sell a position once its sell value reaches this multiple of its cost
+    pub take_profit_multiple: Option<f64>,
+    /// Sell a position once its sell value is down this percentage from cost
+    pub stop_loss_percent: Option<f64>,
+    /// Sell a position after holding it this long (seconds)
+    pub max_hold_secs: Option<u64>,
+    /// Sell a position once its bonding curve is this far (percent) to completion
+    pub sell_at_curve_progress: Option<f64>,
+    /// Named exit strategies (ladders, trailing stops, ...)
+    pub exit_profiles: Vec<(String, ExitStrategy)>,
+    /// Exit profile to use; overrides the take-profit/stop-loss/hold settings
+    pub exit_profile: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            ws_url: None,
             yellowstone_grpc_url: None,
+            secondary_grpc_urls: vec![],
+            race_detection: false,
+            geyser_commitment: CommitmentLevel::Confirmed,
+            geyser_account_include: vec![PUMPFUN_PROGRAM_ID.to_string()],
+            geyser_account_exclude: vec![],
+            geyser_account_required: vec![],
+            geyser_subscribe_bonding_curves: false,
+            geyser_max_reconnect_delay_ms: 30_000,
             private_key: None,
             mnemonic: None,
             buy_amount_sol: 0.1,
             priority_fee_micro_lamports: 100_000, // 0.0001 SOL
+            priority_fee_strategy: PriorityFeeStrategy::Fixed,
+            priority_fee_percentile: 75,
+            priority_fee_multiplier: 1.0,
+            priority_fee_cap_micro_lamports: 5_000_000,
+            priority_fee_refresh_ms: 1_000,
             min_initial_liquidity_sol: 0.0,
             max_initial_liquidity_sol: None,
             blacklisted_creators: vec![],
@@ -62,10 +198,30 @@ impl Default for Config {
             jito_enabled: false,
             jito_tip_lamports: 10_000,
             jito_block_engine_url: None,
+            send_rpc_urls: vec![],
             max_compute_units: 1_400_000,
+            confirm_commitment: CommitmentLevel::Confirmed,
+            blockhash_refresh_ms: 400,
+            retry_fee_bump_percent: 25,
+            simulate_transactions: false,
+            compute_unit_margin_percent: 20,
             slippage_bps: 50,
             use_websocket_fallback: true,
+            use_polling_fallback: true,
             rate_limit_ms: 100,
+            max_in_flight_snipes: 4,
+            max_event_age_ms: 3_000,
+            event_queue_capacity: 1000,
+            ledger_path: "buy_ledger.json".to_string(),
+            max_buys_per_mint: 1,
+            max_buys_per_creator: 0,
+            ledger_retention_hours: 168,
+            take_profit_multiple: None,
+            stop_loss_percent: None,
+            max_hold_secs: None,
+            sell_at_curve_progress: None,
+            exit_profiles: vec![],
+            exit_profile: None,
         }
     }
 }
@@ -79,10 +235,49 @@ impl Config {
             config.rpc_url = rpc_url;
         }
 
+        if let Ok(ws_url) = std::env::var("WS_URL") {
+            config.ws_url = Some(ws_url);
+        }
+
         if let Ok(grpc_url) = std::env::var("YELLOWSTONE_GRPC_URL") {
             config.yellowstone_grpc_url = Some(grpc_url);
         }
 
+        if let Ok(urls) = std::env::var("SECONDARY_GRPC_URLS") {
+            config.secondary_grpc_urls = parse_list(&urls);
+        }
+
+        if let Ok(race) = std::env::var("RACE_DETECTION") {
+            config.race_detection = race.to_lowercase() == "true" || race == "1";
+        }
+
+        if let Ok(commitment) = std::env::var("GEYSER_COMMITMENT") {
+            config.geyser_commitment = CommitmentLevel::from_str(&commitment.to_lowercase())
+                .map_err(|e| anyhow::anyhow!("Invalid GEYSER_COMMITMENT: {}", e))?;
+        }
+
+        if let Ok(include) = std::env::var("GEYSER_ACCOUNT_INCLUDE") {
+            config.geyser_account_include = parse_list(&include);
+        }
+
+        if let Ok(exclude) = std::env::var("GEYSER_ACCOUNT_EXCLUDE") {
+            config.geyser_account_exclude = parse_list(&exclude);
+        }
+
+        if let Ok(required) = std::env::var("GEYSER_ACCOUNT_REQUIRED") {
+            config.geyser_account_required = parse_list(&required);
+        }
+
+        if let Ok(subscribe) = std::env::var("GEYSER_SUBSCRIBE_BONDING_CURVES") {
+            config.geyser_subscribe_bonding_curves =
+                subscribe.to_lowercase() == "true" || subscribe == "1";
+        }
+
+        if let Ok(max_delay) = std::env::var("GEYSER_MAX_RECONNECT_DELAY_MS") {
+            config.geyser_max_reconnect_delay_ms = u64::from_str(&max_delay)
+                .map_err(|e| anyhow::anyhow!("Invalid GEYSER_MAX_RECONNECT_DELAY_MS: {}", e))?;
+        }
+
         if let Ok(private_key) = std::env::var("PRIVATE_KEY_BASE58") {
             config.private_key = Some(private_key);
         }
@@ -101,6 +296,31 @@ impl Config {
                 .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_MICRO_LAMPORTS: {}", e))?;
         }
 
+        if let Ok(strategy) = std::env::var("PRIORITY_FEE_STRATEGY") {
+            config.priority_fee_strategy = PriorityFeeStrategy::from_str(&strategy)
+                .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_STRATEGY: {}", e))?;
+        }
+
+        if let Ok(percentile) = std::env::var("PRIORITY_FEE_PERCENTILE") {
+            config.priority_fee_percentile = u8::from_str(&percentile)
+                .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_PERCENTILE: {}", e))?;
+        }
+
+        if let Ok(multiplier) = std::env::var("PRIORITY_FEE_MULTIPLIER") {
+            config.priority_fee_multiplier = f64::from_str(&multiplier)
+                .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_MULTIPLIER: {}", e))?;
+        }
+
+        if let Ok(cap) = std::env::var("PRIORITY_FEE_CAP_MICRO_LAMPORTS") {
+            config.priority_fee_cap_micro_lamports = u64::from_str(&cap)
+                .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_CAP_MICRO_LAMPORTS: {}", e))?;
+        }
+
+        if let Ok(refresh) = std::env::var("PRIORITY_FEE_REFRESH_MS") {
+            config.priority_fee_refresh_ms = u64::from_str(&refresh)
+                .map_err(|e| anyhow::anyhow!("Invalid PRIORITY_FEE_REFRESH_MS: {}", e))?;
+        }
+
         if let Ok(min_liq) = std::env::var("MIN_INITIAL_LIQUIDITY_SOL") {
             config.min_initial_liquidity_sol = f64::from_str(&min_liq)
                 .map_err(|e| anyhow::anyhow!("Invalid MIN_INITIAL_LIQUIDITY_SOL: {}", e))?;
@@ -112,11 +332,7 @@ impl Config {
         }
 
         if let Ok(blacklist) = std::env::var("BLACKLISTED_CREATORS") {
-            config.blacklisted_creators = blacklist
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
+            config.blacklisted_creators = parse_list(&blacklist);
         }
 
         if let Ok(dry_run) = std::env::var("DRY_RUN") {
@@ -136,11 +352,39 @@ impl Config {
             config.jito_block_engine_url = Some(jito_url);
         }
 
+        if let Ok(urls) = std::env::var("SEND_RPC_URLS") {
+            config.send_rpc_urls = parse_list(&urls);
+        }
+
         if let Ok(compute_units) = std::env::var("MAX_COMPUTE_UNITS") {
             config.max_compute_units = u32::from_str(&compute_units)
                 .map_err(|e| anyhow::anyhow!("Invalid MAX_COMPUTE_UNITS: {}", e))?;
         }
 
+        if let Ok(commitment) = std::env::var("CONFIRM_COMMITMENT") {
+            config.confirm_commitment = CommitmentLevel::from_str(&commitment.to_lowercase())
+                .map_err(|e| anyhow::anyhow!("Invalid CONFIRM_COMMITMENT: {}", e))?;
+        }
+
+        if let Ok(refresh) = std::env::var("BLOCKHASH_REFRESH_MS") {
+            config.blockhash_refresh_ms = u64::from_str(&refresh)
+                .map_err(|e| anyhow::anyhow!("Invalid BLOCKHASH_REFRESH_MS: {}", e))?;
+        }
+
+        if let Ok(bump) = std::env::var("RETRY_FEE_BUMP_PERCENT") {
+            config.retry_fee_bump_percent = u64::from_str(&bump)
+                .map_err(|e| anyhow::anyhow!("Invalid RETRY_FEE_BUMP_PERCENT: {}", e))?;
+        }
+
+        if let Ok(simulate) = std::env::var("SIMULATE_TRANSACTIONS") {
+            config.simulate_transactions = simulate.to_lowercase() == "true" || simulate == "1";
+        }
+
+        if let Ok(margin) = std::env::var("COMPUTE_UNIT_MARGIN_PERCENT") {
+            config.compute_unit_margin_percent = u64::from_str(&margin)
+                .map_err(|e| anyhow::anyhow!("Invalid COMPUTE_UNIT_MARGIN_PERCENT: {}", e))?;
+        }
+
         if let Ok(slippage) = std::env::var("SLIPPAGE_BPS") {
             config.slippage_bps = u16::from_str(&slippage)
                 .map_err(|e| anyhow::anyhow!("Invalid SLIPPAGE_BPS: {}", e))?;
@@ -150,14 +394,129 @@ impl Config {
             config.use_websocket_fallback = use_ws.to_lowercase() == "true" || use_ws == "1";
         }
 
+        if let Ok(use_polling) = std::env::var("USE_POLLING_FALLBACK") {
+            config.use_polling_fallback = use_polling.to_lowercase() == "true" || use_polling == "1";
+        }
+
         if let Ok(rate_limit) = std::env::var("RATE_LIMIT_MS") {
             config.rate_limit_ms = u64::from_str(&rate_limit)
                 .map_err(|e| anyhow::anyhow!("Invalid RATE_LIMIT_MS: {}", e))?;
         }
 
+        if let Ok(max_in_flight) = std::env::var("MAX_IN_FLIGHT_SNIPES") {
+            config.max_in_flight_snipes = usize::from_str(&max_in_flight)
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid MAX_IN_FLIGHT_SNIPES: must be a positive integer"))?;
+        }
+
+        if let Ok(max_age) = std::env::var("MAX_EVENT_AGE_MS") {
+            config.max_event_age_ms = u64::from_str(&max_age)
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_EVENT_AGE_MS: {}", e))?;
+        }
+
+        if let Ok(capacity) = std::env::var("EVENT_QUEUE_CAPACITY") {
+            config.event_queue_capacity = usize::from_str(&capacity)
+                .ok()
+                .filter(|&capacity| capacity > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid EVENT_QUEUE_CAPACITY: must be a positive integer"))?;
+        }
+
+        if let Ok(ledger_path) = std::env::var("LEDGER_PATH") {
+            config.ledger_path = ledger_path;
+        }
+
+        if let Ok(max_buys) = std::env::var("MAX_BUYS_PER_MINT") {
+            config.max_buys_per_mint = u32::from_str(&max_buys)
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_BUYS_PER_MINT: {}", e))?;
+        }
+
+        if let Ok(max_buys) = std::env::var("MAX_BUYS_PER_CREATOR") {
+            config.max_buys_per_creator = u32::from_str(&max_buys)
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_BUYS_PER_CREATOR: {}", e))?;
+        }
+
+        if let Ok(retention) = std::env::var("LEDGER_RETENTION_HOURS") {
+            config.ledger_retention_hours = u64::from_str(&retention)
+                .map_err(|e| anyhow::anyhow!("Invalid LEDGER_RETENTION_HOURS: {}", e))?;
+        }
+
+        if let Ok(take_profit) = std::env::var("TAKE_PROFIT_MULTIPLE") {
+            config.take_profit_multiple = Some(f64::from_str(&take_profit)
+                .map_err(|e| anyhow::anyhow!("Invalid TAKE_PROFIT_MULTIPLE: {}", e))?);
+        }
+
+        if let Ok(stop_loss) = std::env::var("STOP_LOSS_PERCENT") {
+            config.stop_loss_percent = Some(f64::from_str(&stop_loss)
+                .map_err(|e| anyhow::anyhow!("Invalid STOP_LOSS_PERCENT: {}", e))?);
+        }
+
+        if let Ok(max_hold) = std::env::var("MAX_HOLD_SECS") {
+            config.max_hold_secs = Some(u64::from_str(&max_hold)
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_HOLD_SECS: {}", e))?);
+        }
+
+        if let Ok(progress) = std::env::var("SELL_AT_CURVE_PROGRESS") {
+            config.sell_at_curve_progress = Some(f64::from_str(&progress)
+                .map_err(|e| anyhow::anyhow!("Invalid SELL_AT_CURVE_PROGRESS: {}", e))?);
+        }
+
+        if let Ok(profiles) = std::env::var("EXIT_PROFILES") {
+            config.exit_profiles = exit_strategy::parse_profiles(&profiles)
+                .map_err(|e| anyhow::anyhow!("Invalid EXIT_PROFILES: {}", e))?;
+        }
+
+        if let Ok(profile) = std::env::var("EXIT_PROFILE") {
+            config.exit_profile = Some(profile);
+        }
+
         Ok(config)
     }
 
+    /// WebSocket endpoint, derived from the RPC URL when not set explicitly
+    pub fn websocket_url(&self) -> String {
+        if let Some(ref ws_url) = self.ws_url {
+            return ws_url.clone();
+        }
+
+        if let Some(rest) = self.rpc_url.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = self.rpc_url.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            self.rpc_url.clone()
+        }
+    }
+
+    /// Exit strategy for bought positions, if any exit rule is configured
+    ///
+    /// The selected exit profile wins; otherwise the take-profit, stop-loss
+    /// and hold settings make up a simple strategy.
+    pub fn exit_strategy(&self) -> anyhow::Result<Option<ExitStrategy>> {
+        if let Some(ref name) = self.exit_profile {
+            return self
+                .exit_profiles
+                .iter()
+                .find(|(profile, _)| profile == name)
+                .map(|(_, strategy)| Some(strategy.clone()))
+                .ok_or_else(|| anyhow::anyhow!("Unknown exit profile '{}'", name));
+        }
+
+        if self.take_profit_multiple.is_none()
+            && self.stop_loss_percent.is_none()
+            && self.max_hold_secs.is_none()
+            && self.sell_at_curve_progress.is_none()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(ExitStrategy::simple(
+            self.take_profit_multiple,
+            self.stop_loss_percent,
+            self.max_hold_secs,
+        )))
+    }
+
     /// Apply CLI arguments to override config
     pub fn apply_cli_args(&mut self, args: &CliArgs) {
         if let Some(rpc_url) = &args.rpc_url {
@@ -168,6 +527,10 @@ impl Config {
             self.yellowstone_grpc_url = Some(grpc_url.clone());
         }
 
+        if args.race_detection {
+            self.race_detection = true;
+        }
+
         if let Some(buy_amount) = args.buy_amount {
             self.buy_amount_sol = buy_amount;
         }
@@ -195,9 +558,26 @@ impl Config {
         if args.jito_bundle {
             self.jito_enabled = true;
         }
+
+        if args.simulate {
+            self.simulate_transactions = true;
+        }
+
+        if let Some(ref profile) = args.exit_profile {
+            self.exit_profile = Some(profile.clone());
+        }
     }
 }
 
+/// Split a comma-separated environment value into trimmed, non-empty entries
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 /// CLI arguments structure
 #[derive(Debug, Clone, clap::Parser)]
 #[command(name = "pumpfun-sniper")]
@@ -211,6 +591,10 @@ pub struct CliArgs {
     #[arg(long, env = "YELLOWSTONE_GRPC_URL")]
     pub yellowstone_grpc_url: Option<String>,
 
+    /// Run all detection sources concurrently and take the first report
+    #[arg(long)]
+    pub race_detection: bool,
+
     /// Buy amount in SOL
     #[arg(long)]
     pub buy_amount: Option<f64>,
@@ -239,7 +623,37 @@ pub struct CliArgs {
     #[arg(long)]
     pub jito_bundle: bool,
 
+    /// Simulate transactions before sending them
+    #[arg(long)]
+    pub simulate: bool,
+
+    /// Exit profile from EXIT_PROFILES to manage positions with
+    #[arg(long)]
+    pub exit_profile: Option<String>,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info")]
     pub log_level: String,
+
+    /// One-off command to run instead of sniping
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// One-off commands
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum Command {
+    /// Sell tokens of a mint back to its bonding curve
+    Sell {
+        /// Token mint address
+        mint: Pubkey,
+
+        /// Number of tokens to sell, in UI units
+        #[arg(long, conflicts_with = "percent", required_unless_present = "percent")]
+        amount: Option<f64>,
+
+        /// Percentage of the wallet's holdings to sell (0-100]
+        #[arg(long)]
+        percent: Option<f64>,
+    },
 }
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient};
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, signature::Signature, transaction::TransactionError,
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, sleep, timeout, Duration};
+use tokio_stream::StreamExt;
+
+use crate::config::Config;
+
+/// How often the block height is checked for blockhash expiry
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Poll interval when confirming without a WebSocket subscription
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Longest wait for one transaction; a blockhash expires well within this,
+/// so it only ends waits the RPC cannot answer
+const MAX_WAIT: Duration = Duration::from_secs(90);
+
+/// How a sent transaction ended up
+#[derive(Debug, Clone)]
+pub enum TxOutcome {
+    /// Reached the target commitment and succeeded
+    Confirmed { slot: u64 },
+    /// Reached the target commitment but failed on-chain
+    LandedFailed { slot: u64, error: TransactionError },
+    /// The blockhash expired without the transaction landing, or the wait
+    /// timed out before either could be seen
+    Expired { last_valid_block_height: u64 },
+}
+
+/// Where a signature stands right now
+enum SignatureState {
+    NotFound,
+    /// Landed below the target commitment, or the lookup failed
+    Pending,
+    Final(TxOutcome),
+}
+
+/// Waits for sent transactions through `signatureSubscribe`
+///
+/// A transaction counts as expired once the block height passes its
+/// blockhash's last valid block height without it landing, or once
+/// `MAX_WAIT` has passed. Falls back to polling `getSignatureStatuses` when
+/// the WebSocket is unavailable. One WebSocket connection is shared by all
+/// waits and reopened after it fails.
+pub struct Confirmer {
+    ws_url: String,
+    commitment: CommitmentConfig,
+    pubsub: Mutex<Option<Arc<PubsubClient>>>,
+}
+
+impl Confirmer {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            ws_url: config.websocket_url(),
+            commitment: CommitmentConfig {
+                commitment: config.confirm_commitment,
+            },
+            pubsub: Mutex::new(None),
+        }
+    }
+
+    /// Wait until the transaction reaches the target commitment or expires
+    pub async fn wait(
+        &self,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> TxOutcome {
+        match timeout(MAX_WAIT, self.wait_any(rpc_client, signature, last_valid_block_height)).await {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                log::warn!("Gave up confirming {} after {:?}", signature, MAX_WAIT);
+                TxOutcome::Expired { last_valid_block_height }
+            }
+        }
+    }
+
+    async fn wait_any(
+        &self,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> TxOutcome {
+        match self.pubsub().await {
+            Ok(pubsub) => match self
+                .wait_subscribed(&pubsub, rpc_client, signature, last_valid_block_height)
+                .await
+            {
+                Ok(outcome) => return outcome,
+                Err(e) => {
+                    log::warn!("signatureSubscribe for {} failed, polling instead: {:#}", signature, e);
+                    // Reconnect on the next wait, unless another wait already has
+                    let mut shared = self.pubsub.lock().await;
+                    if shared.as_ref().is_some_and(|client| Arc::ptr_eq(client, &pubsub)) {
+                        *shared = None;
+                    }
+                }
+            },
+            Err(e) => log::warn!("Failed to connect for signatureSubscribe, polling instead: {:#}", e),
+        }
+
+        self.wait_polling(rpc_client, signature, last_valid_block_height).await
+    }
+
+    /// Shared WebSocket connection, opened on first use
+    async fn pubsub(&self) -> Result<Arc<PubsubClient>> {
+        let mut pubsub = self.pubsub.lock().await;
+        if let Some(client) = pubsub.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let client = Arc::new(
+            PubsubClient::new(&self.ws_url)
+                .await
+                .context("Failed to connect to the WebSocket endpoint")?,
+        );
+        *pubsub = Some(client.clone());
+        Ok(client)
+    }
+
+    async fn wait_subscribed(
+        &self,
+        pubsub: &PubsubClient,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> Result<TxOutcome> {
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(self.commitment),
+            enable_received_notification: Some(false),
+        };
+        let (mut notifications, _unsubscribe) = pubsub
+            .signature_subscribe(signature, Some(config))
+            .await
+            .context("Failed to subscribe to signature")?;
+
+        // It may have reached the commitment before the subscription existed
+        if let SignatureState::Final(outcome) = self.state(rpc_client, signature).await {
+            return Ok(outcome);
+        }
+
+        let mut expiry_check = interval(EXPIRY_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                notification = notifications.next() => {
+                    let Some(notification) = notification else {
+                        anyhow::bail!("Signature subscription closed");
+                    };
+                    if let RpcSignatureResult::ProcessedSignature(result) = notification.value {
+                        let slot = notification.context.slot;
+                        return Ok(match result.err {
+                            Some(error) => TxOutcome::LandedFailed { slot, error },
+                            None => TxOutcome::Confirmed { slot },
+                        });
+                    }
+                }
+                _ = expiry_check.tick() => {
+                    if let Some(outcome) = self.check_expiry(rpc_client, signature, last_valid_block_height).await {
+                        return Ok(outcome);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn wait_polling(
+        &self,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> TxOutcome {
+        loop {
+            if let Some(outcome) = self.check_expiry(rpc_client, signature, last_valid_block_height).await {
+                return outcome;
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Final outcome if the signature has one or its blockhash has expired
+    async fn check_expiry(
+        &self,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> Option<TxOutcome> {
+        // Read the height first: a signature still unknown after the
+        // blockhash expired can no longer land
+        let block_height = rpc_client.get_block_height().await;
+
+        match self.state(rpc_client, signature).await {
+            SignatureState::Final(outcome) => Some(outcome),
+            SignatureState::Pending => None,
+            SignatureState::NotFound => match block_height {
+                Ok(height) if height > last_valid_block_height => {
+                    Some(TxOutcome::Expired { last_valid_block_height })
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    log::debug!("Failed to get block height: {}", e);
+                    None
+                }
+            },
+        }
+    }
+
+    async fn state(&self, rpc_client: &RpcClient, signature: &Signature) -> SignatureState {
+        let status = match rpc_client.get_signature_statuses(&[*signature]).await {
+            Ok(response) => response.value.into_iter().next().flatten(),
+            Err(e) => {
+                // Never let a failed lookup pass for "not found", which
+                // would count as expired and lead to a resend
+                log::debug!("Error checking transaction status: {}", e);
+                return SignatureState::Pending;
+            }
+        };
+
+        let Some(status) = status else {
+            return SignatureState::NotFound;
+        };
+        if !status.satisfies_commitment(self.commitment) {
+            return SignatureState::Pending;
+        }
+
+        SignatureState::Final(match status.err {
+            Some(error) => TxOutcome::LandedFailed { slot: status.slot, error },
+            None => TxOutcome::Confirmed { slot: status.slot },
+        })
+    }
+}
@@ -1,10 +1,40 @@
 use anyhow::{Context, Result};
-use solana_sdk::pubkey::Pubkey;
+use solana_client::nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses, UiTransactionEncoding,
+};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, sleep, Duration};
 use tokio_stream::StreamExt;
+use yellowstone_grpc::proto::geyser::{
+    subscribe_request_filter_accounts_filter::Filter as AccountsFilter,
+    subscribe_request_filter_accounts_filter_memcmp::Data as MemcmpData,
+    subscribe_update::UpdateOneof, CommitmentLevel as GeyserCommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterAccountsFilterMemcmp, SubscribeRequestFilterTransactions,
+    SubscribeUpdate, SubscribeUpdateAccount, SubscribeUpdateTransaction,
+};
 
-use crate::config::{Config, PUMPFUN_PROGRAM_ID};
-use crate::instructions::{extract_create_accounts, CreateAccounts};
+use crate::bonding_curve::BondingCurve;
+use crate::config::{Config, BONDING_CURVE_DISCRIMINATOR, PUMPFUN_PROGRAM_ID};
+use crate::events::{self, CreateEvent, PumpEvent};
+use crate::instructions::{discriminators, extract_create_accounts, parse_create_instruction};
+use crate::utils::RecentSet;
 
 /// Token creation event detected from Pump.fun
 #[derive(Debug, Clone)]
@@ -12,15 +42,169 @@ pub struct TokenCreationEvent {
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub creator: Pubkey,
+    /// Token program that owns the mint
+    pub token_program: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
     pub signature: String,
     pub slot: u64,
     pub timestamp: i64,
+    /// When the detector decoded the create, on the local clock; the create
+    /// itself landed earlier, in `slot`
+    pub detected_at: Instant,
 }
 
-/// Token detector using Yellowstone Geyser gRPC or WebSocket fallback
+/// How far a bonding curve has got at the end of its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveStage {
+    /// All curve tokens sold; the curve no longer trades
+    Completed,
+    /// Liquidity moved to the PumpSwap pool
+    Migrated,
+}
+
+/// A bonding curve completing or migrating
+#[derive(Debug, Clone)]
+pub struct CurveCompletionEvent {
+    pub mint: Pubkey,
+    pub stage: CurveStage,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// A bonding curve account as streamed by Geyser
+#[derive(Debug, Clone)]
+pub struct CurveUpdate {
+    pub bonding_curve: Pubkey,
+    pub curve: BondingCurve,
+    pub slot: u64,
+}
+
+/// What a Geyser transaction update carried
+struct ParsedUpdate {
+    create: Option<TokenCreationEvent>,
+    completions: Vec<CurveCompletionEvent>,
+}
+
+/// Mint, accounts and metadata of a decoded Create, before it is tied to
+/// the transaction it arrived in
+struct CreateDetails {
+    mint: Pubkey,
+    bonding_curve: Pubkey,
+    creator: Pubkey,
+    token_program: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+impl CreateDetails {
+    /// Details from a CreateEvent, which does not name the token program;
+    /// it comes from the create instruction or the mint account's owner
+    fn from_event(event: CreateEvent, token_program: Pubkey) -> Self {
+        Self {
+            mint: event.mint,
+            bonding_curve: event.bonding_curve,
+            creator: event.creator,
+            token_program,
+            name: event.name,
+            symbol: event.symbol,
+            uri: event.uri,
+        }
+    }
+
+    fn into_event(self, signature: String, slot: u64, timestamp: i64) -> TokenCreationEvent {
+        TokenCreationEvent {
+            mint: self.mint,
+            bonding_curve: self.bonding_curve,
+            creator: self.creator,
+            token_program: self.token_program,
+            name: self.name,
+            symbol: self.symbol,
+            uri: self.uri,
+            signature,
+            slot,
+            timestamp,
+            detected_at: Instant::now(),
+        }
+    }
+}
+
+/// Initial delay before reconnecting a dropped Geyser stream
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// How long a Geyser stream must stay up before its reconnect backoff resets
+const STABLE_STREAM_DURATION: Duration = Duration::from_secs(60);
+
+/// How long to wait before trying `from_slot` again on an endpoint that rejected it
+const RESUME_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Log line the Pump.fun program emits when handling a Create instruction
+const CREATE_LOG: &str = "Program log: Instruction: Create";
+
+/// Signatures per `getSignaturesForAddress` page (the RPC maximum)
+const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// Pages of signatures read per poll before the rest are skipped
+const MAX_SIGNATURE_PAGES: usize = 10;
+
+/// Number of recent create signatures remembered for deduplication
+const SEEN_SIGNATURES_CAPACITY: usize = 10_000;
+
+/// Number of recently emitted mints the race merger remembers, so each
+/// mint is emitted once however late another source reports it
+const RACE_EMITTED_CAPACITY: usize = 10_000;
+
+/// How long the race merger attributes late reports of a mint to lag
+const RACE_MINT_TTL: Duration = Duration::from_secs(60);
+
+/// How often the race merger forgets winners older than `RACE_MINT_TTL`
+const RACE_MINT_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often per-source statistics are logged
+const SOURCE_STATS_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Completion events buffered per subscriber
+const COMPLETIONS_CAPACITY: usize = 256;
+
+/// Bonding curve updates buffered per subscriber; every trade on every
+/// curve produces one
+const CURVE_UPDATES_CAPACITY: usize = 4096;
+
+/// Per-source statistics when racing detection sources
+#[derive(Debug, Clone, Default)]
+pub struct SourceStats {
+    pub label: String,
+    /// Mints this source reported before any other source
+    pub first_seen: u64,
+    /// Mints this source reported after another source already had
+    pub late: u64,
+    /// Total time this source trailed the winning source (ms)
+    pub total_lag_ms: u64,
+    /// Times the source's stream was reconnected (Geyser only)
+    pub reconnects: u64,
+}
+
+impl SourceStats {
+    /// Average time this source trailed the winner when it was late (ms)
+    pub fn avg_lag_ms(&self) -> f64 {
+        if self.late == 0 {
+            0.0
+        } else {
+            self.total_lag_ms as f64 / self.late as f64
+        }
+    }
+}
+
+/// Token detector using Yellowstone Geyser gRPC, with WebSocket logs and
+/// RPC polling as fallbacks, or all of them raced against each other
 pub struct TokenDetector {
     config: Config,
     pumpfun_program_id: Pubkey,
+    source_stats: Arc<Mutex<Vec<SourceStats>>>,
+    completions: broadcast::Sender<CurveCompletionEvent>,
+    curve_updates: broadcast::Sender<CurveUpdate>,
 }
 
 impl TokenDetector {
@@ -31,21 +215,49 @@ impl TokenDetector {
         Ok(Self {
             config,
             pumpfun_program_id,
+            source_stats: Arc::new(Mutex::new(Vec::new())),
+            completions: broadcast::channel(COMPLETIONS_CAPACITY).0,
+            curve_updates: broadcast::channel(CURVE_UPDATES_CAPACITY).0,
         })
     }
 
+    /// Subscribe to bonding curve completions and migrations
+    ///
+    /// Reported by the Geyser and WebSocket sources while detection runs;
+    /// the same event may arrive more than once.
+    pub fn completions(&self) -> broadcast::Receiver<CurveCompletionEvent> {
+        self.completions.subscribe()
+    }
+
+    /// Subscribe to bonding curve account updates
+    ///
+    /// Only streamed by Geyser sources, and only with
+    /// `GEYSER_SUBSCRIBE_BONDING_CURVES` enabled.
+    pub fn curve_updates(&self) -> broadcast::Receiver<CurveUpdate> {
+        self.curve_updates.subscribe()
+    }
+
     /// Start detecting new token creations
     /// 
     /// Returns a stream of TokenCreationEvent
     pub async fn start_detection(
         &self,
     ) -> Result<tokio_stream::wrappers::ReceiverStream<TokenCreationEvent>> {
+        if self.config.race_detection {
+            return self.start_racing_detection().await;
+        }
+
         // Try Yellowstone Geyser gRPC first if configured
         if let Some(ref grpc_url) = self.config.yellowstone_grpc_url {
             log::info!("Attempting to connect to Yellowstone Geyser gRPC: {}", grpc_url);
-            match self.start_geyser_stream(grpc_url).await {
+            match self.start_geyser_stream(grpc_url, "geyser").await {
                 Ok(stream) => {
                     log::info!("Successfully connected to Yellowstone Geyser");
+                    *self.source_stats.lock().unwrap() = vec![SourceStats {
+                        label: "geyser".to_string(),
+                        ..Default::default()
+                    }];
+                    self.start_stats_logging();
                     return Ok(stream);
                 }
                 Err(e) => {
@@ -58,126 +270,611 @@ impl TokenDetector {
             }
         }
 
-        // Fallback to WebSocket subscription
-        log::info!("Using WebSocket subscription as detection method");
-        self.start_websocket_subscription().await
+        // Fallback to WebSocket log subscription
+        log::info!("Using WebSocket logsSubscribe as detection method");
+        match self.start_logs_subscription().await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => {
+                log::warn!("Failed to start WebSocket log subscription: {}", e);
+                if !self.config.use_polling_fallback {
+                    return Err(e);
+                }
+                log::info!("Falling back to RPC polling");
+            }
+        }
+
+        // Last resort: RPC polling
+        log::info!("Using RPC polling as detection method");
+        self.start_polling().await
+    }
+
+    /// Run every configured detection source concurrently
+    ///
+    /// All Geyser endpoints are started, plus WebSocket logs and RPC polling
+    /// when their fallbacks are enabled. Each mint is emitted once, from
+    /// whichever source reports it first. Later reports within
+    /// `RACE_MINT_TTL` feed the per-source lag statistics; later still,
+    /// they are only dropped.
+    async fn start_racing_detection(
+        &self,
+    ) -> Result<tokio_stream::wrappers::ReceiverStream<TokenCreationEvent>> {
+        let mut sources = Vec::new();
+
+        let grpc_urls = self
+            .config
+            .yellowstone_grpc_url
+            .iter()
+            .chain(self.config.secondary_grpc_urls.iter());
+        for (index, grpc_url) in grpc_urls.enumerate() {
+            let label = format!("geyser#{}", index + 1);
+            match self.start_geyser_stream(grpc_url, &label).await {
+                Ok(stream) => sources.push((label, stream)),
+                Err(e) => log::warn!("Failed to start Geyser source {}: {}", grpc_url, e),
+            }
+        }
+
+        if self.config.use_websocket_fallback {
+            match self.start_logs_subscription().await {
+                Ok(stream) => sources.push(("websocket".to_string(), stream)),
+                Err(e) => log::warn!("Failed to start WebSocket source: {}", e),
+            }
+        }
+
+        if self.config.use_polling_fallback {
+            match self.start_polling().await {
+                Ok(stream) => sources.push(("polling".to_string(), stream)),
+                Err(e) => log::warn!("Failed to start polling source: {}", e),
+            }
+        }
+
+        if sources.is_empty() {
+            anyhow::bail!("No detection source could be started");
+        }
+
+        log::info!(
+            "Racing {} detection sources: {}",
+            sources.len(),
+            sources.iter().map(|(label, _)| label.as_str()).collect::<Vec<_>>().join(", ")
+        );
+
+        *self.source_stats.lock().unwrap() = sources
+            .iter()
+            .map(|(label, _)| SourceStats {
+                label: label.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        let (merged_tx, mut merged_rx) = mpsc::channel::<(usize, TokenCreationEvent)>(self.config.event_queue_capacity);
+        for (index, (_, mut stream)) in sources.into_iter().enumerate() {
+            let merged_tx = merged_tx.clone();
+            tokio::spawn(async move {
+                while let Some(event) = stream.next().await {
+                    if merged_tx.send((index, event)).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(merged_tx);
+
+        let (tx, rx) = mpsc::channel(self.config.event_queue_capacity);
+        let source_stats = self.source_stats.clone();
+
+        tokio::spawn(async move {
+            let mut first_seen: HashMap<Pubkey, (usize, Instant)> = HashMap::new();
+            let mut emitted = RecentSet::new(RACE_EMITTED_CAPACITY);
+            let mut prune = interval(RACE_MINT_PRUNE_INTERVAL);
+
+            loop {
+                let (index, event) = tokio::select! {
+                    received = merged_rx.recv() => match received {
+                        Some(received) => received,
+                        None => break,
+                    },
+                    _ = prune.tick() => {
+                        first_seen.retain(|_, (_, seen_at)| seen_at.elapsed() < RACE_MINT_TTL);
+                        continue;
+                    }
+                };
+                let now = Instant::now();
+
+                if let Some(&(winner, seen_at)) = first_seen.get(&event.mint) {
+                    if index != winner {
+                        let lag = now.duration_since(seen_at);
+                        let mut stats = source_stats.lock().unwrap();
+                        stats[index].late += 1;
+                        stats[index].total_lag_ms += lag.as_millis() as u64;
+                        log::debug!(
+                            "{} reported {} {}ms after {}",
+                            stats[index].label,
+                            event.mint,
+                            lag.as_millis(),
+                            stats[winner].label
+                        );
+                    }
+                    continue;
+                }
+
+                // Past the lag window, but already emitted
+                if !emitted.insert(event.mint) {
+                    log::debug!(
+                        "{} reported {} more than {:?} after it was emitted",
+                        source_stats.lock().unwrap()[index].label,
+                        event.mint,
+                        RACE_MINT_TTL
+                    );
+                    continue;
+                }
+
+                first_seen.insert(event.mint, (index, now));
+
+                {
+                    let mut stats = source_stats.lock().unwrap();
+                    stats[index].first_seen += 1;
+                    log::debug!("{} was first to report {}", stats[index].label, event.mint);
+                }
+
+                if let Err(e) = tx.send(event).await {
+                    log::error!("Failed to send token creation event: {}", e);
+                    break;
+                }
+            }
+        });
+
+        self.start_stats_logging();
+
+        Ok(tokio_stream::wrappers::ReceiverStream::new(rx))
+    }
+
+    /// Log per-source statistics every `SOURCE_STATS_INTERVAL` until the
+    /// detector is dropped
+    fn start_stats_logging(&self) {
+        let source_stats = Arc::downgrade(&self.source_stats);
+
+        tokio::spawn(async move {
+            let mut ticker = interval(SOURCE_STATS_INTERVAL);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let Some(source_stats) = source_stats.upgrade() else {
+                    break;
+                };
+                log_source_stats(&source_stats.lock().unwrap());
+            }
+        });
+    }
+
+    /// Log a summary line per detection source
+    pub fn log_stats(&self) {
+        log_source_stats(&self.source_stats());
+    }
+
+    /// Snapshot of per-source statistics
+    ///
+    /// Covers every raced source, or the Geyser stream alone when not
+    /// racing; empty for the WebSocket and polling fallbacks.
+    pub fn source_stats(&self) -> Vec<SourceStats> {
+        self.source_stats.lock().unwrap().clone()
     }
 
     /// Start Yellowstone Geyser gRPC stream
+    ///
+    /// The first connection is made here so callers can fall back when Geyser
+    /// is unreachable; after that a supervisor task keeps the stream alive.
     async fn start_geyser_stream(
         &self,
         grpc_url: &str,
+        label: &str,
     ) -> Result<tokio_stream::wrappers::ReceiverStream<TokenCreationEvent>> {
-        use yellowstone_grpc::{
-            geyser::SubscribeRequest,
-            proto::geyser::SubscribeRequestFilterAccounts,
-        };
+        let (tx, rx) = tokio::sync::mpsc::channel(self.config.event_queue_capacity);
+
+        let request = self.build_subscribe_request();
+        let stream = Self::subscribe_geyser(grpc_url, request.clone()).await?;
 
-        let (tx, rx) = tokio::sync::mpsc::channel(1000);
+        tokio::spawn(Self::supervise_geyser_stream(
+            grpc_url.to_string(),
+            label.to_string(),
+            request,
+            stream,
+            self.pumpfun_program_id,
+            Duration::from_millis(self.config.geyser_max_reconnect_delay_ms),
+            self.source_stats.clone(),
+            self.completions.clone(),
+            self.curve_updates.clone(),
+            tx,
+        ));
 
-        // Create gRPC client
+        Ok(tokio_stream::wrappers::ReceiverStream::new(rx))
+    }
+
+    /// Connect to Geyser and open a subscription
+    async fn subscribe_geyser(
+        grpc_url: &str,
+        request: SubscribeRequest,
+    ) -> Result<tonic::Streaming<SubscribeUpdate>> {
         let mut client = yellowstone_grpc::GeyserGrpcClient::connect(grpc_url)
             .await
             .context("Failed to connect to Yellowstone Geyser")?;
 
-        // Subscribe to Pump.fun program transactions
-        let filter = SubscribeRequestFilterAccounts {
-            account: vec![self.pumpfun_program_id.to_string()],
-            owner: vec![],
-            filters: vec![],
-        };
-
-        let request = SubscribeRequest {
-            slots: vec![],
-            accounts: vec![filter],
-            transactions: vec![],
-            transactions_status: vec![],
-            blocks: vec![],
-            blocks_meta: vec![],
-            accounts_data_slice: vec![],
-            commitment: Some(yellowstone_grpc::proto::geyser::CommitmentLevel::Confirmed as i32),
-        };
-
-        // Spawn task to handle stream
-        let mut stream = client
+        client
             .subscribe_once(request)
             .await
-            .context("Failed to subscribe to Geyser stream")?;
+            .context("Failed to subscribe to Geyser stream")
+    }
 
-        let pumpfun_program_id = self.pumpfun_program_id;
-        let config = self.config.clone();
+    /// Drive the Geyser stream, reconnecting with exponential backoff
+    ///
+    /// Reconnects resubscribe with `from_slot` set to the last slot seen so the
+    /// provider replays the gap; replayed creates are dropped by signature. If
+    /// the endpoint rejects `from_slot`, it is resubscribed without it until
+    /// `RESUME_RETRY_INTERVAL` has passed. The backoff only resets once a
+    /// stream has stayed up for `STABLE_STREAM_DURATION`, so a flapping
+    /// endpoint is not hammered. Each endpoint runs its own supervisor.
+    async fn supervise_geyser_stream(
+        grpc_url: String,
+        label: String,
+        request: SubscribeRequest,
+        mut stream: tonic::Streaming<SubscribeUpdate>,
+        program_id: Pubkey,
+        max_reconnect_delay: Duration,
+        source_stats: Arc<Mutex<Vec<SourceStats>>>,
+        completions: broadcast::Sender<CurveCompletionEvent>,
+        curve_updates: broadcast::Sender<CurveUpdate>,
+        tx: mpsc::Sender<TokenCreationEvent>,
+    ) {
+        let mut seen_signatures = RecentSet::new(SEEN_SIGNATURES_CAPACITY);
+        let mut last_slot: Option<u64> = None;
+        let mut resume_disabled_until: Option<Instant> = None;
+        let mut reconnects = 0u64;
+        let mut delay = INITIAL_RECONNECT_DELAY;
 
-        tokio::spawn(async move {
-            while let Some(msg) = stream.message().await.transpose() {
-                match msg {
-                    Ok(update) => {
-                        // Parse transaction update
-                        if let Some(tx_update) = update.transaction {
-                            if let Some(event) = Self::parse_transaction_update(&tx_update, &pumpfun_program_id) {
+        loop {
+            let connected_at = Instant::now();
+            loop {
+                match stream.message().await {
+                    Ok(Some(update)) => match update.update_oneof {
+                        Some(UpdateOneof::Transaction(tx_update)) => {
+                            last_slot = Some(last_slot.map_or(tx_update.slot, |slot| slot.max(tx_update.slot)));
+
+                            let Some(parsed) = Self::parse_transaction_update(&tx_update, &program_id) else {
+                                continue;
+                            };
+
+                            for completion in parsed.completions {
+                                // No subscribers is fine
+                                let _ = completions.send(completion);
+                            }
+
+                            if let Some(event) = parsed.create {
+                                if !seen_signatures.insert(event.signature.clone()) {
+                                    log::debug!("Skipping replayed create: {}", event.signature);
+                                    continue;
+                                }
                                 if let Err(e) = tx.send(event).await {
                                     log::error!("Failed to send token creation event: {}", e);
-                                    break;
+                                    return;
                                 }
                             }
                         }
+                        Some(UpdateOneof::Account(account_update)) => {
+                            if let Some(update) = Self::parse_curve_update(&account_update) {
+                                // No subscribers is fine
+                                let _ = curve_updates.send(update);
+                            }
+                        }
+                        _ => {}
+                    },
+                    Ok(None) => {
+                        log::warn!("Geyser stream closed by server");
+                        break;
                     }
                     Err(e) => {
                         log::warn!("Error receiving Geyser update: {}", e);
+                        break;
                     }
                 }
             }
+
+            if connected_at.elapsed() >= STABLE_STREAM_DURATION {
+                delay = INITIAL_RECONNECT_DELAY;
+            }
+
+            stream = loop {
+                if tx.is_closed() {
+                    return;
+                }
+
+                sleep(delay).await;
+                delay = (delay * 2).min(max_reconnect_delay);
+
+                reconnects += 1;
+                let attempt = reconnects;
+                if let Some(source) = source_stats.lock().unwrap().iter_mut().find(|source| source.label == label) {
+                    source.reconnects = reconnects;
+                }
+
+                if resume_disabled_until.is_some_and(|until| Instant::now() >= until) {
+                    log::info!("Trying from_slot on {} again", label);
+                    resume_disabled_until = None;
+                }
+                let from_slot = if resume_disabled_until.is_none() { last_slot } else { None };
+
+                let mut resume_request = request.clone();
+                resume_request.from_slot = from_slot;
+
+                match Self::subscribe_geyser(&grpc_url, resume_request).await {
+                    Ok(stream) => {
+                        log::info!(
+                            "Reconnected {} to Yellowstone Geyser (reconnect #{}, resuming from slot {:?})",
+                            label,
+                            attempt,
+                            from_slot
+                        );
+                        break stream;
+                    }
+                    Err(e) if from_slot.is_some() => {
+                        log::warn!("{} resubscribe from slot {:?} failed: {}", label, from_slot, e);
+
+                        // Retry straight away without from_slot to tell an
+                        // unsupported resume apart from an unreachable endpoint
+                        if let Ok(stream) = Self::subscribe_geyser(&grpc_url, request.clone()).await {
+                            log::warn!(
+                                "{} rejected from_slot; updates since slot {:?} were not replayed, retrying resume in {:?}",
+                                label,
+                                from_slot,
+                                RESUME_RETRY_INTERVAL
+                            );
+                            resume_disabled_until = Some(Instant::now() + RESUME_RETRY_INTERVAL);
+                            break stream;
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("{} reconnect #{} failed: {}", label, attempt, e);
+                    }
+                }
+            };
+        }
+    }
+
+    /// Build the Geyser subscribe request from the configured filter set
+    ///
+    /// Always subscribes to non-vote, successful transactions matching the
+    /// account filters; bonding curve account updates are opt-in.
+    fn build_subscribe_request(&self) -> SubscribeRequest {
+        let mut transactions = HashMap::new();
+        transactions.insert(
+            "pumpfun".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                signature: None,
+                account_include: self.config.geyser_account_include.clone(),
+                account_exclude: self.config.geyser_account_exclude.clone(),
+                account_required: self.config.geyser_account_required.clone(),
+            },
+        );
+
+        let mut accounts = HashMap::new();
+        if self.config.geyser_subscribe_bonding_curves {
+            accounts.insert(
+                "bonding_curves".to_string(),
+                SubscribeRequestFilterAccounts {
+                    account: vec![],
+                    owner: vec![self.pumpfun_program_id.to_string()],
+                    filters: vec![SubscribeRequestFilterAccountsFilter {
+                        filter: Some(AccountsFilter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                            offset: 0,
+                            data: Some(MemcmpData::Bytes(BONDING_CURVE_DISCRIMINATOR.to_vec())),
+                        })),
+                    }],
+                    ..Default::default()
+                },
+            );
+        }
+
+        let commitment = match self.config.geyser_commitment {
+            CommitmentLevel::Processed => GeyserCommitmentLevel::Processed,
+            CommitmentLevel::Finalized => GeyserCommitmentLevel::Finalized,
+            _ => GeyserCommitmentLevel::Confirmed,
+        };
+
+        SubscribeRequest {
+            accounts,
+            transactions,
+            commitment: Some(commitment as i32),
+            ..Default::default()
+        }
+    }
+
+    /// Start `logsSubscribe` WebSocket detection (first fallback)
+    ///
+    /// Creates are spotted from the program's instruction logs; only those
+    /// transactions are then fetched to resolve the mint and creator.
+    async fn start_logs_subscription(
+        &self,
+    ) -> Result<tokio_stream::wrappers::ReceiverStream<TokenCreationEvent>> {
+        let (tx, rx) = tokio::sync::mpsc::channel(self.config.event_queue_capacity);
+        let ws_url = self.config.websocket_url();
+
+        // Connect once up front so callers can fall back to polling
+        let pubsub = PubsubClient::new(&ws_url)
+            .await
+            .context("Failed to connect to WebSocket endpoint")?;
+
+        let rpc_client = Arc::new(RpcClient::new_with_commitment(
+            self.config.rpc_url.clone(),
+            CommitmentConfig::confirmed(),
+        ));
+        let pumpfun_program_id = self.pumpfun_program_id;
+        let max_reconnect_delay = Duration::from_millis(self.config.geyser_max_reconnect_delay_ms);
+        let completions = self.completions.clone();
+
+        tokio::spawn(async move {
+            let mut pubsub = Some(pubsub);
+            let mut delay = INITIAL_RECONNECT_DELAY;
+
+            loop {
+                let client = match pubsub.take() {
+                    Some(client) => client,
+                    None => match PubsubClient::new(&ws_url).await {
+                        Ok(client) => {
+                            log::info!("Reconnected to WebSocket endpoint");
+                            client
+                        }
+                        Err(e) => {
+                            log::warn!("WebSocket reconnect failed: {}", e);
+                            sleep(delay).await;
+                            delay = (delay * 2).min(max_reconnect_delay);
+                            continue;
+                        }
+                    },
+                };
+
+                let subscription = client
+                    .logs_subscribe(
+                        RpcTransactionLogsFilter::Mentions(vec![pumpfun_program_id.to_string()]),
+                        RpcTransactionLogsConfig {
+                            commitment: Some(CommitmentConfig::confirmed()),
+                        },
+                    )
+                    .await;
+
+                let (mut notifications, _unsubscribe) = match subscription {
+                    Ok(subscription) => subscription,
+                    Err(e) => {
+                        log::warn!("logsSubscribe failed: {}", e);
+                        sleep(delay).await;
+                        delay = (delay * 2).min(max_reconnect_delay);
+                        continue;
+                    }
+                };
+                delay = INITIAL_RECONNECT_DELAY;
+
+                while let Some(response) = notifications.next().await {
+                    let logs = response.value;
+                    if logs.err.is_some() {
+                        continue;
+                    }
+
+                    let logged_events = events::parse_logs(&logs.logs, &pumpfun_program_id);
+                    for completion in find_completions(logged_events.iter().cloned(), &logs.signature, response.context.slot) {
+                        let _ = completions.send(completion);
+                    }
+
+                    if !logs.logs.iter().any(|line| line == CREATE_LOG) {
+                        continue;
+                    }
+
+                    log::debug!("Create spotted in logs: {}", logs.signature);
+
+                    let create_event = logged_events
+                        .into_iter()
+                        .find_map(|event| match event {
+                            PumpEvent::Create(create) => Some(create),
+                            _ => None,
+                        });
+
+                    // A logged CreateEvent only lacks the token program, read
+                    // from the mint's owner; otherwise the whole transaction
+                    // is fetched. Both happen off the notification path so a
+                    // slow fetch never delays the next log
+                    let rpc_client = rpc_client.clone();
+                    let tx = tx.clone();
+                    let slot = response.context.slot;
+                    tokio::spawn(async move {
+                        let event = match create_event {
+                            Some(create) => match Self::fetch_token_program(&rpc_client, &create.mint).await {
+                                Some(token_program) => {
+                                    let timestamp = create.timestamp;
+                                    Some(CreateDetails::from_event(create, token_program).into_event(
+                                        logs.signature,
+                                        slot,
+                                        timestamp,
+                                    ))
+                                }
+                                None => {
+                                    log::warn!("Could not resolve the token program of {}", create.mint);
+                                    None
+                                }
+                            },
+                            None => match Self::fetch_transaction(&rpc_client, &logs.signature).await {
+                                Some(tx_data) => Self::parse_transaction(&tx_data, &pumpfun_program_id, &logs.signature),
+                                None => {
+                                    log::warn!("Could not fetch create transaction {}", logs.signature);
+                                    None
+                                }
+                            },
+                        };
+
+                        if let Some(event) = event {
+                            if let Err(e) = tx.send(event).await {
+                                log::error!("Failed to send token creation event: {}", e);
+                            }
+                        }
+                    });
+                }
+
+                if tx.is_closed() {
+                    return;
+                }
+                log::warn!("WebSocket log subscription ended; reconnecting");
+            }
         });
 
         Ok(tokio_stream::wrappers::ReceiverStream::new(rx))
     }
 
-    /// Start WebSocket subscription (fallback method)
-    async fn start_websocket_subscription(
+    /// Start RPC polling detection (last resort)
+    ///
+    /// Polls `getSignaturesForAddress` for signatures newer than the last one
+    /// seen and fetches each transaction. Slow and credit-hungry; prefer
+    /// Geyser or WebSocket logs.
+    async fn start_polling(
         &self,
     ) -> Result<tokio_stream::wrappers::ReceiverStream<TokenCreationEvent>> {
-        use solana_client::nonblocking::rpc_client::RpcClient;
-
-        let (tx, rx) = tokio::sync::mpsc::channel(1000);
+        let (tx, rx) = tokio::sync::mpsc::channel(self.config.event_queue_capacity);
         let rpc_url = self.config.rpc_url.clone();
         let pumpfun_program_id = self.pumpfun_program_id;
 
         // Spawn task to poll for new transactions
         let config_clone = self.config.clone();
         tokio::spawn(async move {
-            let client = RpcClient::new(rpc_url);
-            let mut last_signature: Option<String> = None;
+            let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+            let mut last_signature: Option<Signature> = None;
 
             loop {
-                // Get recent signatures for the program
-                match client
-                    .get_signatures_for_address(&pumpfun_program_id)
-                    .await
-                {
+                // Get signatures newer than the last one processed
+                match Self::fetch_new_signatures(&client, &pumpfun_program_id, last_signature).await {
                     Ok(signatures) => {
-                        for sig_info in signatures.iter().take(10) {
-                            // Skip if we've already processed this
-                            if let Some(ref last) = last_signature {
-                                if sig_info.signature == *last {
-                                    break;
-                                }
+                        let is_first_poll = last_signature.is_none();
+
+                        if let Some(first) = signatures.first() {
+                            last_signature = Signature::from_str(&first.signature).ok();
+                        }
+
+                        // The first poll only establishes a starting point;
+                        // after that, process oldest first to keep chain order
+                        let new_signatures = if is_first_poll { &signatures[..0] } else { &signatures[..] };
+
+                        for sig_info in new_signatures.iter().rev() {
+                            if sig_info.err.is_some() {
+                                continue;
                             }
 
-                            // Parse transaction
-                            if let Ok(tx_data) = client.get_transaction(&sig_info.signature, solana_transaction_status::UiTransactionEncoding::Json).await {
+                            if let Some(tx_data) = Self::fetch_transaction(&client, &sig_info.signature).await {
                                 if let Some(event) = Self::parse_transaction(&tx_data, &pumpfun_program_id, &sig_info.signature) {
                                     if let Err(e) = tx.send(event).await {
                                         log::error!("Failed to send token creation event: {}", e);
+                                        return;
                                     }
                                 }
                             }
                         }
-
-                        if let Some(first) = signatures.first() {
-                            last_signature = Some(first.signature.clone());
-                        }
                     }
                     Err(e) => {
-                        log::warn!("Error fetching signatures: {}", e);
+                        log::warn!("Error fetching signatures: {:#}", e);
                     }
                 }
 
@@ -192,111 +889,487 @@ impl TokenDetector {
         Ok(tokio_stream::wrappers::ReceiverStream::new(rx))
     }
 
+    /// Signatures of the program newer than `until`, newest first
+    ///
+    /// Pages back with `before` until the RPC reaches `until`, up to
+    /// `MAX_SIGNATURE_PAGES`; older signatures past that are skipped with a
+    /// warning. Without `until` only the newest page is read.
+    async fn fetch_new_signatures(
+        client: &RpcClient,
+        program_id: &Pubkey,
+        until: Option<Signature>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let mut signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = Vec::new();
+
+        for _ in 0..MAX_SIGNATURE_PAGES {
+            let before = match signatures.last() {
+                Some(oldest) => Some(Signature::from_str(&oldest.signature).context("Invalid signature from RPC")?),
+                None => None,
+            };
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURES_PAGE_LIMIT),
+                commitment: Some(CommitmentConfig::confirmed()),
+            };
+            let page = client
+                .get_signatures_for_address_with_config(program_id, config)
+                .await
+                .context("Failed to fetch program signatures")?;
+
+            let reached_until = page.len() < SIGNATURES_PAGE_LIMIT;
+            signatures.extend(page);
+            if reached_until || until.is_none() {
+                return Ok(signatures);
+            }
+        }
+
+        log::warn!(
+            "More than {} new program signatures since the last poll; skipping older ones",
+            signatures.len()
+        );
+        Ok(signatures)
+    }
+
+    /// Token program owning a mint, retrying briefly while the account propagates
+    async fn fetch_token_program(client: &RpcClient, mint: &Pubkey) -> Option<Pubkey> {
+        const MAX_ATTEMPTS: u32 = 3;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            match client.get_account(mint).await {
+                Ok(account) => return Some(account.owner),
+                Err(e) => {
+                    log::debug!("get_account {} failed (attempt {}): {}", mint, attempt, e);
+                    sleep(Duration::from_millis(200 * attempt as u64)).await;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Fetch a confirmed transaction, retrying briefly while it propagates
+    async fn fetch_transaction(
+        client: &RpcClient,
+        signature: &str,
+    ) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
+        const MAX_ATTEMPTS: u32 = 3;
+
+        let signature = Signature::from_str(signature).ok()?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            match client.get_transaction_with_config(&signature, config).await {
+                Ok(tx_data) => return Some(tx_data),
+                Err(e) => {
+                    log::debug!("get_transaction {} failed (attempt {}): {}", signature, attempt, e);
+                    sleep(Duration::from_millis(200 * attempt as u64)).await;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Decode a bonding curve account update from Geyser
+    fn parse_curve_update(update: &SubscribeUpdateAccount) -> Option<CurveUpdate> {
+        let account = update.account.as_ref()?;
+        let bonding_curve = Pubkey::try_from(account.pubkey.as_slice()).ok()?;
+
+        match BondingCurve::decode(&account.data) {
+            Ok(curve) => Some(CurveUpdate {
+                bonding_curve,
+                curve,
+                slot: update.slot,
+            }),
+            Err(e) => {
+                log::debug!("Undecodable bonding curve update for {}: {:#}", bonding_curve, e);
+                None
+            }
+        }
+    }
+
     /// Parse transaction update from Geyser
+    ///
+    /// Resolves the full account key list (static keys followed by addresses
+    /// loaded from lookup tables) and scans both top-level and inner
+    /// instructions, so creates routed through other programs are caught too.
+    /// Curve completions and migrations in the same transaction are returned
+    /// alongside.
     fn parse_transaction_update(
-        _update: &yellowstone_grpc::proto::geyser::TransactionUpdate,
-        _program_id: &Pubkey,
-    ) -> Option<TokenCreationEvent> {
-        // Parse Geyser transaction update
-        // This is a simplified version - actual implementation depends on Geyser message format
-        // You'll need to:
-        // 1. Extract transaction data
-        // 2. Check for Create instruction discriminator
-        // 3. Extract accounts (mint, bonding_curve, creator)
-        // 4. Return TokenCreationEvent
-        
-        // Placeholder - implement based on actual Geyser message structure
-        None
+        update: &SubscribeUpdateTransaction,
+        program_id: &Pubkey,
+    ) -> Option<ParsedUpdate> {
+        let info = update.transaction.as_ref()?;
+        let message = info.transaction.as_ref()?.message.as_ref()?;
+        let meta = info.meta.as_ref();
+
+        // A failed create never produces a token
+        if meta.map_or(false, |meta| meta.err.is_some()) {
+            return None;
+        }
+
+        let mut account_keys = message
+            .account_keys
+            .iter()
+            .map(|key| Pubkey::try_from(key.as_slice()).ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        if let Some(meta) = meta {
+            for key in meta
+                .loaded_writable_addresses
+                .iter()
+                .chain(meta.loaded_readonly_addresses.iter())
+            {
+                account_keys.push(Pubkey::try_from(key.as_slice()).ok()?);
+            }
+        }
+
+        let outer = message
+            .instructions
+            .iter()
+            .map(|ix| (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice()));
+        let inner = meta
+            .into_iter()
+            .flat_map(|meta| meta.inner_instructions.iter())
+            .flat_map(|inner| inner.instructions.iter())
+            .map(|ix| (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice()));
+        let instructions: Vec<_> = outer.chain(inner).collect();
+
+        let logs = meta.map_or(&[][..], |meta| meta.log_messages.as_slice());
+        let signature = bs58::encode(&info.signature).into_string();
+
+        let cpi_events = instructions
+            .iter()
+            .filter(|(program_id_index, _, _)| account_keys.get(*program_id_index as usize) == Some(program_id))
+            .filter_map(|(_, _, data)| events::decode_cpi_event(data));
+        let completions = find_completions(
+            cpi_events.chain(events::parse_logs(logs, program_id)),
+            &signature,
+            update.slot,
+        );
+
+        let create = find_create(&account_keys, program_id, instructions.into_iter(), logs)
+            .map(|create| create.into_event(signature, update.slot, chrono::Utc::now().timestamp()));
+
+        Some(ParsedUpdate { create, completions })
     }
 
     /// Parse transaction from RPC
     fn parse_transaction(
-        tx: &solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
         program_id: &Pubkey,
         signature: &str,
     ) -> Option<TokenCreationEvent> {
-        use solana_transaction_status::UiTransactionEncoding;
+        let meta = tx.transaction.meta.as_ref();
+
+        // A failed create never produces a token
+        if meta.map_or(false, |meta| meta.err.is_some()) {
+            return None;
+        }
+
+        let transaction = tx.transaction.transaction.decode()?;
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
 
-        // Extract transaction data
-        let transaction = match tx.transaction {
-            solana_transaction_status::EncodedTransaction::Json(ref json_tx) => {
-                json_tx
+        if let Some(meta) = meta {
+            if let Some(loaded) = Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()) {
+                for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                    account_keys.push(Pubkey::from_str(key).ok()?);
+                }
             }
-            _ => return None,
-        };
+        }
 
-        // Find instructions for Pump.fun program
-        if let Some(ref message) = transaction.message {
-            if let Some(ref account_keys) = message.account_keys {
-                // Find program index
-                let program_index = account_keys
-                    .iter()
-                    .position(|key| key == program_id.to_string())?;
-
-                // Find Create instruction
-                if let Some(ref instructions) = message.instructions {
-                    for ix in instructions {
-                        if let Some(program_id_index) = ix.program_id_index {
-                            if program_id_index as usize == program_index {
-                                // Check if this is a Create instruction
-                                if let Some(create_accounts) = Self::parse_create_instruction(
-                                    &ix.data,
-                                    account_keys,
-                                    &ix.accounts,
-                                ) {
-                                    return Some(TokenCreationEvent {
-                                        mint: create_accounts.mint,
-                                        bonding_curve: create_accounts.bonding_curve,
-                                        creator: create_accounts.creator,
-                                        signature: signature.to_string(),
-                                        slot: tx.slot.unwrap_or(0),
-                                        timestamp: chrono::Utc::now().timestamp(),
-                                    });
-                                }
-                            }
-                        }
+        let mut instructions: Vec<(u32, Vec<u8>, Vec<u8>)> = transaction
+            .message
+            .instructions()
+            .iter()
+            .map(|ix| (ix.program_id_index as u32, ix.accounts.clone(), ix.data.clone()))
+            .collect();
+
+        if let Some(inner) = meta.and_then(|meta| Option::<Vec<UiInnerInstructions>>::from(meta.inner_instructions.clone())) {
+            for ix in inner.iter().flat_map(|inner| inner.instructions.iter()) {
+                if let UiInstruction::Compiled(ix) = ix {
+                    if let Ok(data) = bs58::decode(&ix.data).into_vec() {
+                        instructions.push((ix.program_id_index as u32, ix.accounts.clone(), data));
                     }
                 }
             }
         }
 
-        None
+        let logs = meta
+            .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages.clone()))
+            .unwrap_or_default();
+
+        let create = find_create(
+            &account_keys,
+            program_id,
+            instructions
+                .iter()
+                .map(|(program_id_index, accounts, data)| (*program_id_index, accounts.as_slice(), data.as_slice())),
+            &logs,
+        )?;
+
+        Some(create.into_event(
+            signature.to_string(),
+            tx.slot,
+            tx.block_time.unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        ))
     }
+}
 
-    /// Parse Create instruction from instruction data
-    fn parse_create_instruction(
-        data: &str,
-        account_keys: &[String],
-        account_indices: &[u8],
-    ) -> Option<CreateAccounts> {
-        // Decode base58 instruction data
-        let decoded = bs58::decode(data).into_vec().ok()?;
+/// Curve completions and migrations among a transaction's decoded events,
+/// one per mint and stage
+fn find_completions(
+    events: impl Iterator<Item = PumpEvent>,
+    signature: &str,
+    slot: u64,
+) -> Vec<CurveCompletionEvent> {
+    let mut found: Vec<CurveCompletionEvent> = Vec::new();
 
-        // Check discriminator
-        if decoded.len() < 8 {
-            return None;
+    for event in events {
+        let (mint, stage) = match event {
+            PumpEvent::Complete(complete) => (complete.mint, CurveStage::Completed),
+            PumpEvent::Migration(migration) => (migration.mint, CurveStage::Migrated),
+            PumpEvent::Create(_) | PumpEvent::Trade(_) => continue,
+        };
+
+        if !found.iter().any(|seen| seen.mint == mint && seen.stage == stage) {
+            found.push(CurveCompletionEvent {
+                mint,
+                stage,
+                signature: signature.to_string(),
+                slot,
+            });
         }
+    }
 
-        let discriminator = &decoded[0..8];
-        let create_discriminator = [24, 30, 200, 40, 5, 28, 7, 119];
-        if discriminator != create_discriminator {
-            return None;
+    found
+}
+
+/// Log a summary line per detection source
+fn log_source_stats(stats: &[SourceStats]) {
+    for source in stats {
+        log::info!(
+            "Detection source {}: first={}, late={}, avg lag={:.0}ms, reconnects={}",
+            source.label,
+            source.first_seen,
+            source.late,
+            source.avg_lag_ms(),
+            source.reconnects
+        );
+    }
+}
+
+/// Find and decode the first Pump.fun Create in a transaction
+///
+/// Each instruction is given as (program id index, account indices, data),
+/// indexing into the transaction's fully resolved account key list. Metadata
+/// comes from the program's CreateEvent for the same mint (self-CPI or
+/// `Program data:` log) when there is one, otherwise from the instruction
+/// arguments; the token program always comes from the instruction's accounts.
+fn find_create<'a>(
+    account_keys: &[Pubkey],
+    program_id: &Pubkey,
+    instructions: impl Iterator<Item = (u32, &'a [u8], &'a [u8])>,
+    logs: &[String],
+) -> Option<CreateDetails> {
+    let mut from_instruction = None;
+    let mut cpi_events = Vec::new();
+
+    for (program_id_index, accounts, data) in instructions {
+        if account_keys.get(program_id_index as usize) != Some(program_id) {
+            continue;
         }
 
-        // Extract accounts (order depends on instruction format)
-        // This is simplified - verify with actual IDL
-        if account_indices.len() < 4 {
-            return None;
+        if let Some(PumpEvent::Create(create)) = events::decode_cpi_event(data) {
+            cpi_events.push(create);
+            continue;
         }
 
-        let mint = Pubkey::from_str(account_keys.get(account_indices[0] as usize)?).ok()?;
-        let bonding_curve = Pubkey::from_str(account_keys.get(account_indices[1] as usize)?).ok()?;
-        let creator = Pubkey::from_str(account_keys.get(account_indices[2] as usize)?).ok()?;
+        if from_instruction.is_some() || !data.starts_with(&discriminators::CREATE) {
+            continue;
+        }
 
-        Some(CreateAccounts {
-            mint,
-            bonding_curve,
-            creator,
-        })
+        if let Some(create_accounts) = extract_create_accounts(account_keys, accounts) {
+            let args = parse_create_instruction(data);
+            from_instruction = Some(CreateDetails {
+                mint: create_accounts.mint,
+                bonding_curve: create_accounts.bonding_curve,
+                creator: args
+                    .as_ref()
+                    .and_then(|args| args.creator)
+                    .unwrap_or(create_accounts.creator),
+                token_program: create_accounts.token_program,
+                name: args.as_ref().map(|args| args.name.clone()).unwrap_or_default(),
+                symbol: args.as_ref().map(|args| args.symbol.clone()).unwrap_or_default(),
+                uri: args.map(|args| args.uri).unwrap_or_default(),
+            });
+        }
+    }
+
+    // Only look for an event when the transaction actually created a token
+    let from_instruction = from_instruction?;
+
+    // A transaction can create several tokens; only this mint's event counts
+    let mint = from_instruction.mint;
+    let event = cpi_events
+        .into_iter()
+        .find(|create| create.mint == mint)
+        .or_else(|| {
+            events::parse_logs(logs, program_id)
+                .into_iter()
+                .find_map(|event| match event {
+                    PumpEvent::Create(create) if create.mint == mint => Some(create),
+                    _ => None,
+                })
+        });
+
+    // The event does not name the token program; keep the instruction's
+    Some(match event {
+        Some(create) => CreateDetails::from_event(create, from_instruction.token_program),
+        None => from_instruction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+    use yellowstone_grpc::proto::geyser::SubscribeUpdate;
+
+    const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+    fn program_id() -> Pubkey {
+        Pubkey::from_str(PUMPFUN_PROGRAM_ID).unwrap()
+    }
+
+    /// Decode a `SubscribeUpdate` fixture into its transaction update
+    ///
+    /// The fixtures in `tests/fixtures/geyser` are synthetic, built by
+    /// `cargo run --example geyser_fixtures`; they are not mainnet recordings.
+    fn transaction_update(fixture: &[u8]) -> SubscribeUpdateTransaction {
+        match SubscribeUpdate::decode(fixture).expect("fixture is a valid SubscribeUpdate").update_oneof {
+            Some(UpdateOneof::Transaction(update)) => update,
+            _ => panic!("fixture is not a transaction update"),
+        }
+    }
+
+    fn parse_create(fixture: &[u8]) -> TokenCreationEvent {
+        let update = transaction_update(fixture);
+        TokenDetector::parse_transaction_update(&update, &program_id())
+            .expect("update parses")
+            .create
+            .expect("update holds a create")
+    }
+
+    #[test]
+    fn decodes_create_from_outer_instruction() {
+        let event = parse_create(include_bytes!("../tests/fixtures/geyser/create_outer.bin"));
+        let mint = Pubkey::from_str("7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr").unwrap();
+
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.bonding_curve, crate::pda::bonding_curve(&program_id(), &mint));
+        assert_eq!(event.creator, Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap());
+        assert_eq!(event.token_program, spl_token::ID);
+        assert_eq!(event.name, "Outer Coin");
+        assert_eq!(event.symbol, "OUTER");
+        assert_eq!(event.uri, "https://ipfs.io/ipfs/outer");
+        assert_eq!(event.signature, bs58::encode([1u8; 64]).into_string());
+        assert_eq!(event.slot, 290_000_001);
+    }
+
+    #[test]
+    fn decodes_create_from_inner_cpi() {
+        let event = parse_create(include_bytes!("../tests/fixtures/geyser/create_inner_cpi.bin"));
+        let mint = Pubkey::from_str("3vz82EWYv8xnc7Cm7qSgERcpMeqw92PcX8PBz88npump").unwrap();
+
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.bonding_curve, crate::pda::bonding_curve(&program_id(), &mint));
+        assert_eq!(event.creator, Pubkey::from_str("5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9").unwrap());
+        // Metadata from the self-CPI CreateEvent, token program from the instruction
+        assert_eq!(event.name, "Routed Coin");
+        assert_eq!(event.symbol, "ROUTE");
+        assert_eq!(event.token_program, Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap());
+        assert_eq!(event.slot, 290_000_002);
+    }
+
+    #[test]
+    fn ignores_create_event_for_another_mint() {
+        let mut update = transaction_update(include_bytes!("../tests/fixtures/geyser/create_inner_cpi.bin"));
+        let mint = Pubkey::from_str("3vz82EWYv8xnc7Cm7qSgERcpMeqw92PcX8PBz88npump").unwrap();
+        let other_mint = Pubkey::new_unique();
+
+        // Point the self-CPI CreateEvent at another mint, under another name
+        let meta = update.transaction.as_mut().unwrap().meta.as_mut().unwrap();
+        let event = meta.inner_instructions[0]
+            .instructions
+            .iter_mut()
+            .find(|instruction| instruction.data.starts_with(&events::EVENT_IX_TAG))
+            .unwrap();
+        let data = &mut event.data;
+        let name = data.windows(11).position(|window| window == b"Routed Coin").unwrap();
+        data[name..name + 11].copy_from_slice(b"Wrong Coin!");
+        let at = data.windows(32).position(|window| window == mint.as_ref()).unwrap();
+        data[at..at + 32].copy_from_slice(other_mint.as_ref());
+
+        let event = TokenDetector::parse_transaction_update(&update, &program_id())
+            .expect("update parses")
+            .create
+            .expect("update holds a create");
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.name, "Routed Coin");
+    }
+
+    #[test]
+    fn resolves_create_accounts_loaded_from_lookup_table() {
+        let event = parse_create(include_bytes!("../tests/fixtures/geyser/create_lookup_table.bin"));
+        let mint = Pubkey::from_str("BqnpCdDLPV2pFdAaLnVidmn3G93RRvr7aS9ZFpFdpump").unwrap();
+
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.bonding_curve, crate::pda::bonding_curve(&program_id(), &mint));
+        assert_eq!(event.creator, Pubkey::from_str("8ExsMZsCnMUhRsgU8LzSDnRSL6WSBezTnq3XzXBQ2S3n").unwrap());
+        assert_eq!(event.token_program, spl_token::ID);
+        assert_eq!(event.name, "Lookup Coin");
+    }
+
+    #[test]
+    fn ignores_failed_transaction() {
+        let update = transaction_update(include_bytes!("../tests/fixtures/geyser/create_failed.bin"));
+
+        assert!(TokenDetector::parse_transaction_update(&update, &program_id()).is_none());
+    }
+
+    #[test]
+    fn decodes_bonding_curve_account_update() {
+        let bonding_curve = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let mut data = BONDING_CURVE_DISCRIMINATOR.to_vec();
+        for reserve in [1_000_000u64, 30_000_000_000, 800_000, 0, 1_000_000_000] {
+            data.extend_from_slice(&reserve.to_le_bytes());
+        }
+        data.push(1);
+        data.extend_from_slice(creator.as_ref());
+
+        let update = SubscribeUpdateAccount {
+            account: Some(yellowstone_grpc::proto::geyser::SubscribeUpdateAccountInfo {
+                pubkey: bonding_curve.to_bytes().to_vec(),
+                owner: program_id().to_bytes().to_vec(),
+                data,
+                ..Default::default()
+            }),
+            slot: 290_000_100,
+            is_startup: false,
+        };
+
+        let parsed = TokenDetector::parse_curve_update(&update).expect("update parses");
+        assert_eq!(parsed.bonding_curve, bonding_curve);
+        assert_eq!(parsed.slot, 290_000_100);
+        assert_eq!(parsed.curve.virtual_sol_reserves, 30_000_000_000);
+        assert!(parsed.curve.complete);
+        assert_eq!(parsed.curve.creator, creator);
     }
 }
@@ -0,0 +1,136 @@
+use solana_client::client_error::ClientError;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
+use std::str::FromStr;
+
+use crate::config::PUMPFUN_PROGRAM_ID;
+use crate::ledger::BuyLimit;
+
+/// First Anchor error code of a program's own errors
+const ANCHOR_ERROR_OFFSET: u32 = 6000;
+
+/// Pump.fun program errors, by Anchor error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PumpError {
+    #[error("not authorized")]
+    NotAuthorized,
+    #[error("slippage: too much SOL required to buy")]
+    TooMuchSolRequired,
+    #[error("slippage: too little SOL received for the sell")]
+    TooLittleSolReceived,
+    #[error("mint does not match the bonding curve")]
+    MintDoesNotMatchBondingCurve,
+    #[error("bonding curve complete")]
+    BondingCurveComplete,
+    #[error("bonding curve not complete")]
+    BondingCurveNotComplete,
+    #[error("program not initialized")]
+    NotInitialized,
+    #[error("buy of zero tokens")]
+    BuyZeroAmount,
+    #[error("not enough tokens left on the curve to buy")]
+    NotEnoughTokensToBuy,
+    #[error("sell of zero tokens")]
+    SellZeroAmount,
+    #[error("not enough tokens to sell")]
+    NotEnoughTokensToSell,
+    #[error("arithmetic overflow")]
+    Overflow,
+    #[error("Pump.fun error code {0}")]
+    Other(u32),
+}
+
+impl PumpError {
+    pub fn from_code(code: u32) -> Self {
+        match code.checked_sub(ANCHOR_ERROR_OFFSET) {
+            Some(0) => Self::NotAuthorized,
+            Some(2) => Self::TooMuchSolRequired,
+            Some(3) => Self::TooLittleSolReceived,
+            Some(4) => Self::MintDoesNotMatchBondingCurve,
+            Some(5) => Self::BondingCurveComplete,
+            Some(6) => Self::BondingCurveNotComplete,
+            Some(7) => Self::NotInitialized,
+            Some(20) => Self::BuyZeroAmount,
+            Some(21) => Self::NotEnoughTokensToBuy,
+            Some(22) => Self::SellZeroAmount,
+            Some(23) => Self::NotEnoughTokensToSell,
+            Some(24) => Self::Overflow,
+            _ => Self::Other(code),
+        }
+    }
+
+    /// Whether the price moved past the transaction's slippage bound
+    pub fn is_slippage(&self) -> bool {
+        matches!(self, Self::TooMuchSolRequired | Self::TooLittleSolReceived)
+    }
+}
+
+/// Why a buy or sell did not go through
+#[derive(Debug, thiserror::Error)]
+pub enum SnipeError {
+    /// The curve moved past the slippage bound before the transaction was sent
+    #[error("curve moved beyond slippage tolerance: {tokens} tokens now cost {cost} lamports, limit {limit}")]
+    SlippageExceeded { tokens: u64, cost: u64, limit: u64 },
+    /// The curve completed; the token now trades on PumpSwap
+    #[error("bonding curve is complete")]
+    CurveComplete,
+    /// The buy ledger already holds as many buys as allowed
+    #[error("buy limit reached for {limit} {key}: {buys} buys already made")]
+    BuyLimitReached { limit: BuyLimit, key: Pubkey, buys: u32 },
+    /// Pump.fun rejected the transaction, in simulation or on-chain
+    #[error("Pump.fun rejected instruction {index}: {error}")]
+    Program { index: u8, error: PumpError },
+    /// Simulation failed for a reason other than a Pump.fun error
+    #[error("simulation failed: {error}")]
+    SimulationFailed { error: TransactionError, logs: Vec<String> },
+    /// The transaction landed but failed
+    #[error("transaction {signature} failed: {error}")]
+    TransactionFailed { signature: String, error: String },
+    /// Sending gave up while the transaction could still land
+    #[error("transaction {signature} was sent but its outcome is unknown")]
+    Unconfirmed { signature: String },
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] ClientError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl SnipeError {
+    /// Decode a transaction error against the instructions that produced it
+    ///
+    /// Custom errors raised by a Pump.fun instruction become
+    /// [`SnipeError::Program`]; anything else is left to `fallback`.
+    pub fn from_transaction_error(
+        error: TransactionError,
+        instructions: &[Instruction],
+        fallback: impl FnOnce(TransactionError) -> Self,
+    ) -> Self {
+        if let TransactionError::InstructionError(index, InstructionError::Custom(code)) = error {
+            let program_id = instructions.get(index as usize).map(|ix| ix.program_id);
+            if program_id == Pubkey::from_str(PUMPFUN_PROGRAM_ID).ok() {
+                return Self::Program {
+                    index,
+                    error: PumpError::from_code(code),
+                };
+            }
+        }
+
+        fallback(error)
+    }
+
+    /// Whether the transaction behind this failure may still land
+    pub fn may_have_landed(&self) -> bool {
+        matches!(self, Self::Unconfirmed { .. })
+    }
+
+    /// The Pump.fun error behind this failure, if any
+    pub fn pump_error(&self) -> Option<PumpError> {
+        match self {
+            Self::Program { error, .. } => Some(*error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use base64::Engine;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::utils::BorshReader;
+
+/// Pump.fun Anchor event discriminators
+pub mod discriminators {
+    /// CreateEvent discriminator
+    pub const CREATE_EVENT: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+
+    /// TradeEvent discriminator
+    pub const TRADE_EVENT: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+    /// CompleteEvent discriminator
+    pub const COMPLETE_EVENT: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+
+    /// CompletePumpAmmMigrationEvent discriminator
+    pub const MIGRATION_EVENT: [u8; 8] = [189, 233, 93, 185, 92, 148, 234, 148];
+}
+
+/// Instruction tag Anchor prefixes to events emitted through a self-CPI
+/// (`emit_cpi!`), ahead of the event discriminator
+pub const EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// Log prefix Anchor uses for events emitted with `emit!`
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Decoded Pump.fun event
+#[derive(Debug, Clone)]
+pub enum PumpEvent {
+    Create(CreateEvent),
+    Trade(TradeEvent),
+    Complete(CompleteEvent),
+    Migration(MigrationEvent),
+}
+
+/// Emitted by the program when a token is created
+#[derive(Debug, Clone)]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+}
+
+impl CreateEvent {
+    fn decode(reader: &mut BorshReader) -> Option<Self> {
+        Some(Self {
+            name: reader.read_string()?,
+            symbol: reader.read_string()?,
+            uri: reader.read_string()?,
+            mint: reader.read_pubkey()?,
+            bonding_curve: reader.read_pubkey()?,
+            user: reader.read_pubkey()?,
+            creator: reader.read_pubkey()?,
+            timestamp: reader.read_i64()?,
+            virtual_token_reserves: reader.read_u64()?,
+            virtual_sol_reserves: reader.read_u64()?,
+            real_token_reserves: reader.read_u64()?,
+            token_total_supply: reader.read_u64()?,
+        })
+    }
+}
+
+/// Emitted by the program for every buy and sell on a bonding curve
+#[derive(Debug, Clone)]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    /// Lamports traded against the curve, fees excluded
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    /// Protocol fee in lamports
+    pub fee: u64,
+    pub creator: Pubkey,
+    pub creator_fee_basis_points: u64,
+    /// Creator fee in lamports
+    pub creator_fee: u64,
+}
+
+impl TradeEvent {
+    /// Fee fields were appended by later program versions and read as zero
+    /// when absent
+    fn decode(reader: &mut BorshReader) -> Option<Self> {
+        Some(Self {
+            mint: reader.read_pubkey()?,
+            sol_amount: reader.read_u64()?,
+            token_amount: reader.read_u64()?,
+            is_buy: reader.read_bool()?,
+            user: reader.read_pubkey()?,
+            timestamp: reader.read_i64()?,
+            virtual_sol_reserves: reader.read_u64()?,
+            virtual_token_reserves: reader.read_u64()?,
+            real_sol_reserves: reader.read_u64()?,
+            real_token_reserves: reader.read_u64()?,
+            fee_recipient: reader.read_pubkey().unwrap_or_default(),
+            fee_basis_points: reader.read_u64().unwrap_or_default(),
+            fee: reader.read_u64().unwrap_or_default(),
+            creator: reader.read_pubkey().unwrap_or_default(),
+            creator_fee_basis_points: reader.read_u64().unwrap_or_default(),
+            creator_fee: reader.read_u64().unwrap_or_default(),
+        })
+    }
+}
+
+/// Emitted when a buy takes the last tokens off a bonding curve
+#[derive(Debug, Clone)]
+pub struct CompleteEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+impl CompleteEvent {
+    fn decode(reader: &mut BorshReader) -> Option<Self> {
+        Some(Self {
+            user: reader.read_pubkey()?,
+            mint: reader.read_pubkey()?,
+            bonding_curve: reader.read_pubkey()?,
+            timestamp: reader.read_i64()?,
+        })
+    }
+}
+
+/// Emitted when a completed curve's liquidity moves to its PumpSwap pool
+#[derive(Debug, Clone)]
+pub struct MigrationEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub mint_amount: u64,
+    pub sol_amount: u64,
+    pub pool_migration_fee: u64,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+    pub pool: Pubkey,
+}
+
+impl MigrationEvent {
+    fn decode(reader: &mut BorshReader) -> Option<Self> {
+        Some(Self {
+            user: reader.read_pubkey()?,
+            mint: reader.read_pubkey()?,
+            mint_amount: reader.read_u64()?,
+            sol_amount: reader.read_u64()?,
+            pool_migration_fee: reader.read_u64()?,
+            bonding_curve: reader.read_pubkey()?,
+            timestamp: reader.read_i64()?,
+            pool: reader.read_pubkey()?,
+        })
+    }
+}
+
+/// Decode an event from its discriminator-prefixed Borsh payload
+///
+/// Trailing bytes are ignored so fields appended by program upgrades do not
+/// break decoding.
+pub fn decode_event(data: &[u8]) -> Option<PumpEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let (discriminator, payload) = data.split_at(8);
+    let mut reader = BorshReader::new(payload);
+
+    match discriminator {
+        d if d == discriminators::CREATE_EVENT => CreateEvent::decode(&mut reader).map(PumpEvent::Create),
+        d if d == discriminators::TRADE_EVENT => TradeEvent::decode(&mut reader).map(PumpEvent::Trade),
+        d if d == discriminators::COMPLETE_EVENT => CompleteEvent::decode(&mut reader).map(PumpEvent::Complete),
+        d if d == discriminators::MIGRATION_EVENT => MigrationEvent::decode(&mut reader).map(PumpEvent::Migration),
+        _ => None,
+    }
+}
+
+/// Decode an event emitted through an Anchor self-CPI
+///
+/// `data` is the inner instruction data sent to the Pump.fun program.
+pub fn decode_cpi_event(data: &[u8]) -> Option<PumpEvent> {
+    decode_event(data.strip_prefix(&EVENT_IX_TAG)?)
+}
+
+/// Decode all Pump.fun events from a transaction's log messages
+///
+/// Tracks the invocation stack so only `Program data:` lines written while
+/// the Pump.fun program is executing are considered.
+pub fn parse_logs(logs: &[String], program_id: &Pubkey) -> Vec<PumpEvent> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            if let Some((program, tail)) = rest.split_once(' ') {
+                if tail.starts_with("invoke [") {
+                    stack.push(program);
+                    continue;
+                }
+                if tail == "success" || tail.starts_with("failed") {
+                    stack.pop();
+                    continue;
+                }
+            }
+        }
+
+        if stack.last() != Some(&program_id.as_str()) {
+            continue;
+        }
+
+        if let Some(encoded) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if let Ok(data) = base64::engine::general_purpose::STANDARD.decode(encoded) {
+                if let Some(event) = decode_event(&data) {
+                    events.push(event);
+                }
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(data: &mut Vec<u8>, value: &str) {
+        data.extend((value.len() as u32).to_le_bytes());
+        data.extend(value.as_bytes());
+    }
+
+    fn create_event_data(mint: &Pubkey, creator: &Pubkey) -> Vec<u8> {
+        let mut data = discriminators::CREATE_EVENT.to_vec();
+        push_string(&mut data, "Test Coin");
+        push_string(&mut data, "TEST");
+        push_string(&mut data, "https://ipfs.io/ipfs/test");
+        for key in [mint, &Pubkey::new_unique(), creator, creator] {
+            data.extend(key.to_bytes());
+        }
+        data.extend(1_730_000_000i64.to_le_bytes());
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000] {
+            data.extend(value.to_le_bytes());
+        }
+        data
+    }
+
+    /// TradeEvent as emitted before the fee fields were appended
+    fn trade_event_data(mint: &Pubkey, user: &Pubkey) -> Vec<u8> {
+        let mut data = discriminators::TRADE_EVENT.to_vec();
+        data.extend(mint.to_bytes());
+        data.extend(1_000_000_000u64.to_le_bytes());
+        data.extend(34_612_903_225_806u64.to_le_bytes());
+        data.push(1);
+        data.extend(user.to_bytes());
+        data.extend(1_730_000_000i64.to_le_bytes());
+        for value in [31_000_000_000u64, 1_038_387_096_774_194, 1_000_000_000, 758_487_096_774_194] {
+            data.extend(value.to_le_bytes());
+        }
+        data
+    }
+
+    fn program_data(data: &[u8]) -> String {
+        format!("{}{}", PROGRAM_DATA_PREFIX, base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    #[test]
+    fn decodes_create_event() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let Some(PumpEvent::Create(event)) = decode_event(&create_event_data(&mint, &creator)) else {
+            panic!("not a CreateEvent");
+        };
+        assert_eq!(event.name, "Test Coin");
+        assert_eq!(event.symbol, "TEST");
+        assert_eq!(event.uri, "https://ipfs.io/ipfs/test");
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.creator, creator);
+        assert_eq!(event.timestamp, 1_730_000_000);
+        assert_eq!(event.virtual_sol_reserves, 30_000_000_000);
+        assert_eq!(event.token_total_supply, 1_000_000_000_000_000);
+    }
+
+    #[test]
+    fn decodes_trade_event_with_and_without_fee_fields() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let mut data = trade_event_data(&mint, &user);
+
+        let Some(PumpEvent::Trade(old)) = decode_event(&data) else {
+            panic!("not a TradeEvent");
+        };
+        assert_eq!(old.mint, mint);
+        assert_eq!(old.user, user);
+        assert!(old.is_buy);
+        assert_eq!(old.sol_amount, 1_000_000_000);
+        assert_eq!(old.token_amount, 34_612_903_225_806);
+        assert_eq!(old.real_token_reserves, 758_487_096_774_194);
+        assert_eq!((old.fee, old.creator_fee), (0, 0));
+
+        data.extend(Pubkey::new_unique().to_bytes());
+        data.extend(95u64.to_le_bytes());
+        data.extend(9_500_000u64.to_le_bytes());
+        data.extend(creator.to_bytes());
+        data.extend(5u64.to_le_bytes());
+        data.extend(500_000u64.to_le_bytes());
+
+        let Some(PumpEvent::Trade(new)) = decode_event(&data) else {
+            panic!("not a TradeEvent");
+        };
+        assert_eq!(new.fee_basis_points, 95);
+        assert_eq!(new.fee, 9_500_000);
+        assert_eq!(new.creator, creator);
+        assert_eq!(new.creator_fee, 500_000);
+    }
+
+    #[test]
+    fn decodes_complete_event() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let bonding_curve = Pubkey::new_unique();
+        let mut data = discriminators::COMPLETE_EVENT.to_vec();
+        for key in [user, mint, bonding_curve] {
+            data.extend(key.to_bytes());
+        }
+        data.extend(1_730_000_000i64.to_le_bytes());
+
+        let Some(PumpEvent::Complete(event)) = decode_event(&data) else {
+            panic!("not a CompleteEvent");
+        };
+        assert_eq!(event.user, user);
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.bonding_curve, bonding_curve);
+
+        // Truncated payloads do not decode
+        assert!(decode_event(&data[..data.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_or_short_discriminators() {
+        let mut data = create_event_data(&Pubkey::new_unique(), &Pubkey::new_unique());
+        data[0] ^= 0xff;
+
+        assert!(decode_event(&data).is_none());
+        assert!(decode_event(&discriminators::CREATE_EVENT[..7]).is_none());
+    }
+
+    #[test]
+    fn decodes_self_cpi_events_only_behind_the_event_tag() {
+        let mint = Pubkey::new_unique();
+        let event = create_event_data(&mint, &Pubkey::new_unique());
+        let mut tagged = EVENT_IX_TAG.to_vec();
+        tagged.extend(&event);
+
+        assert!(matches!(decode_cpi_event(&tagged), Some(PumpEvent::Create(create)) if create.mint == mint));
+        // An instruction whose data is a bare event is not an emitted event
+        assert!(decode_cpi_event(&event).is_none());
+    }
+
+    #[test]
+    fn parses_program_data_logged_by_the_program_only() {
+        let program_id = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ours = create_event_data(&mint, &Pubkey::new_unique());
+        let theirs = trade_event_data(&Pubkey::new_unique(), &Pubkey::new_unique());
+
+        let logs = vec![
+            format!("Program {} invoke [1]", other_program),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: Instruction: Create".to_string(),
+            format!("Program {} invoke [3]", other_program),
+            // Written by the program the Pump.fun program called
+            program_data(&theirs),
+            format!("Program {} success", other_program),
+            program_data(&ours),
+            "Program data: not base64!".to_string(),
+            format!("Program {} success", program_id),
+            // Written by the router after the Pump.fun program returned
+            program_data(&theirs),
+            format!("Program {} success", other_program),
+        ];
+
+        let events = parse_logs(&logs, &program_id);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], PumpEvent::Create(create) if create.mint == mint));
+    }
+}
@@ -0,0 +1,322 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Why (part of) a position was sold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    TrailingStop,
+    MaxHoldTime,
+    /// Bonding curve close to completion
+    CurveProgress,
+}
+
+/// Sell `percent` of the original position once it reaches `multiple`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LadderStep {
+    pub multiple: f64,
+    pub percent: f64,
+}
+
+/// Rules for leaving a position
+///
+/// Multiples compare what the remaining tokens would sell for against what
+/// they cost. Written as comma-separated rules, e.g.
+/// `2x:50,4x:25,trail:30,sl:40,hold:600`: sell 50% of the original position
+/// at 2x, 25% at 4x, the rest once it falls 30% from its peak, everything
+/// at a 40% loss or after 600 seconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExitStrategy {
+    /// Partial take-profits, ascending by multiple
+    pub ladder: Vec<LadderStep>,
+    /// Sell the rest once value falls this percentage from its peak
+    pub trailing_stop_percent: Option<f64>,
+    /// Sell everything once value is down this percentage from cost
+    pub stop_loss_percent: Option<f64>,
+    /// Sell everything after holding this long
+    pub max_hold: Option<Duration>,
+}
+
+/// Progress of a strategy over one position
+#[derive(Debug, Clone, Default)]
+pub struct ExitState {
+    /// Highest multiple observed so far
+    pub peak_multiple: f64,
+    /// Ladder steps already sold
+    pub steps_filled: usize,
+    /// Percentage of the original position already sold
+    pub sold_percent: f64,
+}
+
+/// A sale the strategy calls for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExitDecision {
+    pub reason: ExitReason,
+    /// Fraction of the tokens still held to sell, in (0, 1]
+    pub sell_fraction: f64,
+    /// Ladder steps filled once this sale goes through
+    pub steps_filled: usize,
+}
+
+impl ExitDecision {
+    /// Whether the sale closes the position
+    pub fn closes(&self) -> bool {
+        self.sell_fraction >= 1.0
+    }
+}
+
+impl ExitState {
+    /// Track the peak for the trailing stop
+    pub fn observe(&mut self, multiple: f64) {
+        if multiple > self.peak_multiple {
+            self.peak_multiple = multiple;
+        }
+    }
+
+    /// Record a completed sale
+    pub fn apply(&mut self, decision: &ExitDecision) {
+        self.sold_percent += (100.0 - self.sold_percent) * decision.sell_fraction;
+        self.steps_filled = decision.steps_filled;
+    }
+}
+
+impl ExitStrategy {
+    /// Build a take-profit / stop-loss / hold-time strategy
+    pub fn simple(
+        take_profit_multiple: Option<f64>,
+        stop_loss_percent: Option<f64>,
+        max_hold_secs: Option<u64>,
+    ) -> Self {
+        Self {
+            ladder: take_profit_multiple
+                .map(|multiple| vec![LadderStep { multiple, percent: 100.0 }])
+                .unwrap_or_default(),
+            trailing_stop_percent: None,
+            stop_loss_percent,
+            max_hold: max_hold_secs.map(Duration::from_secs),
+        }
+    }
+
+    /// Next sale due, if any
+    ///
+    /// `multiple` is the current value of the remaining tokens over their
+    /// cost, when known; `held_for` is the time since the position opened.
+    /// Call [`ExitState::observe`] with the multiple first.
+    pub fn next_exit(&self, state: &ExitState, multiple: Option<f64>, held_for: Duration) -> Option<ExitDecision> {
+        let sell_all = |reason| ExitDecision {
+            reason,
+            sell_fraction: 1.0,
+            steps_filled: state.steps_filled,
+        };
+
+        if self.max_hold.is_some_and(|max_hold| held_for >= max_hold) {
+            return Some(sell_all(ExitReason::MaxHoldTime));
+        }
+
+        let multiple = multiple?;
+
+        if let Some(stop_loss) = self.stop_loss_percent {
+            if multiple <= 1.0 - stop_loss / 100.0 {
+                return Some(sell_all(ExitReason::StopLoss));
+            }
+        }
+
+        // Every step reached since the last sale goes out together
+        let reached = self.ladder[state.steps_filled.min(self.ladder.len())..]
+            .iter()
+            .take_while(|step| multiple >= step.multiple)
+            .count();
+        if reached > 0 {
+            let steps_filled = state.steps_filled + reached;
+            let percent: f64 = self.ladder[state.steps_filled..steps_filled]
+                .iter()
+                .map(|step| step.percent)
+                .sum();
+            let remaining = 100.0 - state.sold_percent;
+            let sell_fraction = if remaining <= 0.0 { 1.0 } else { (percent / remaining).min(1.0) };
+
+            return Some(ExitDecision {
+                reason: ExitReason::TakeProfit,
+                sell_fraction,
+                steps_filled,
+            });
+        }
+
+        if let Some(trailing_stop) = self.trailing_stop_percent {
+            if multiple <= state.peak_multiple * (1.0 - trailing_stop / 100.0) {
+                return Some(sell_all(ExitReason::TrailingStop));
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for ExitStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut strategy = Self::default();
+
+        for rule in s.split(',').map(str::trim).filter(|rule| !rule.is_empty()) {
+            let (key, value) = rule
+                .split_once(':')
+                .ok_or_else(|| format!("rule '{}' is not of the form key:value", rule))?;
+            let value = f64::from_str(value.trim()).map_err(|e| format!("rule '{}': {}", rule, e))?;
+            if value <= 0.0 {
+                return Err(format!("rule '{}' must be positive", rule));
+            }
+
+            match key.trim() {
+                "trail" => strategy.trailing_stop_percent = Some(value),
+                "sl" => strategy.stop_loss_percent = Some(value),
+                "hold" => strategy.max_hold = Some(Duration::from_secs_f64(value)),
+                key => {
+                    let multiple = key
+                        .strip_suffix('x')
+                        .and_then(|multiple| f64::from_str(multiple).ok())
+                        .ok_or_else(|| format!("unknown rule '{}' (expected <n>x, trail, sl or hold)", rule))?;
+                    strategy.ladder.push(LadderStep { multiple, percent: value });
+                }
+            }
+        }
+
+        strategy
+            .ladder
+            .sort_by(|a, b| a.multiple.total_cmp(&b.multiple));
+
+        let ladder_percent: f64 = strategy.ladder.iter().map(|step| step.percent).sum();
+        if ladder_percent > 100.0 {
+            return Err(format!("ladder sells {}% of the position", ladder_percent));
+        }
+
+        Ok(strategy)
+    }
+}
+
+/// Parse `name=rules;name=rules` into named strategies
+pub fn parse_profiles(value: &str) -> Result<Vec<(String, ExitStrategy)>, String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|profile| !profile.is_empty())
+        .map(|profile| {
+            let (name, rules) = profile
+                .split_once('=')
+                .ok_or_else(|| format!("profile '{}' is not of the form name=rules", profile))?;
+            let strategy = ExitStrategy::from_str(rules).map_err(|e| format!("profile '{}': {}", name.trim(), e))?;
+            Ok((name.trim().to_string(), strategy))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(rules: &str) -> ExitStrategy {
+        ExitStrategy::from_str(rules).unwrap()
+    }
+
+    /// Feed one multiple per second through the strategy, applying every sale
+    /// until the position closes; returns each sale with its tick
+    fn run(strategy: &ExitStrategy, multiples: &[f64]) -> Vec<(usize, ExitDecision)> {
+        let mut state = ExitState::default();
+        let mut sales = Vec::new();
+
+        for (tick, &multiple) in multiples.iter().enumerate() {
+            state.observe(multiple);
+            if let Some(decision) = strategy.next_exit(&state, Some(multiple), Duration::from_secs(tick as u64)) {
+                state.apply(&decision);
+                sales.push((tick, decision));
+                if decision.closes() {
+                    break;
+                }
+            }
+        }
+
+        sales
+    }
+
+    #[test]
+    fn sells_take_profit_tiers_as_they_are_reached() {
+        let sales = run(&strategy("2x:50,4x:25"), &[1.0, 1.5, 2.1, 3.0, 4.2, 5.0]);
+
+        assert_eq!(
+            sales,
+            vec![
+                (2, ExitDecision { reason: ExitReason::TakeProfit, sell_fraction: 0.5, steps_filled: 1 }),
+                (4, ExitDecision { reason: ExitReason::TakeProfit, sell_fraction: 0.5, steps_filled: 2 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn sells_every_tier_skipped_over_at_once() {
+        let sales = run(&strategy("2x:50,4x:25"), &[1.0, 5.0]);
+
+        assert_eq!(
+            sales,
+            vec![(1, ExitDecision { reason: ExitReason::TakeProfit, sell_fraction: 0.75, steps_filled: 2 })]
+        );
+    }
+
+    #[test]
+    fn trailing_stop_follows_new_highs() {
+        // 1.3 is within 20% of the 1.5 peak; 1.55 would have been too, but
+        // not of the 2.0 peak set afterwards
+        let sales = run(&strategy("trail:20"), &[1.0, 1.5, 1.3, 2.0, 1.7, 1.55, 1.0]);
+
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].0, 5);
+        assert_eq!(sales[0].1.reason, ExitReason::TrailingStop);
+        assert!(sales[0].1.closes());
+    }
+
+    #[test]
+    fn stop_loss_sells_everything() {
+        let sales = run(&strategy("2x:50,sl:40"), &[1.0, 0.9, 0.7, 0.55, 0.3]);
+
+        assert_eq!(
+            sales,
+            vec![(3, ExitDecision { reason: ExitReason::StopLoss, sell_fraction: 1.0, steps_filled: 0 })]
+        );
+    }
+
+    #[test]
+    fn max_hold_sells_without_a_price() {
+        let strategy = strategy("2x:50,hold:60");
+        let state = ExitState::default();
+
+        assert_eq!(strategy.next_exit(&state, None, Duration::from_secs(59)), None);
+
+        let decision = strategy.next_exit(&state, None, Duration::from_secs(60)).unwrap();
+        assert_eq!(decision.reason, ExitReason::MaxHoldTime);
+        assert!(decision.closes());
+    }
+
+    #[test]
+    fn rules_firing_together_resolve_by_precedence() {
+        let strategy = strategy("2x:50,trail:10,sl:40,hold:60");
+        let state = ExitState {
+            peak_multiple: 3.0,
+            ..Default::default()
+        };
+        let exit = |multiple, held_secs| {
+            strategy
+                .next_exit(&state, Some(multiple), Duration::from_secs(held_secs))
+                .unwrap()
+                .reason
+        };
+
+        // Hold time beats everything, even a take-profit
+        assert_eq!(exit(2.5, 60), ExitReason::MaxHoldTime);
+        assert_eq!(exit(0.5, 60), ExitReason::MaxHoldTime);
+        // Stop-loss beats the trailing stop
+        assert_eq!(exit(0.5, 10), ExitReason::StopLoss);
+        // Take-profit beats the trailing stop
+        assert_eq!(exit(2.5, 10), ExitReason::TakeProfit);
+        assert_eq!(exit(1.5, 10), ExitReason::TrailingStop);
+    }
+}
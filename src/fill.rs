@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses, UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use std::str::FromStr;
+use tokio::time::{sleep, Duration};
+
+use crate::config::PUMPFUN_PROGRAM_ID;
+use crate::events::{self, PumpEvent, TradeEvent};
+
+/// Attempts at fetching a confirmed buy; RPC nodes can lag the confirmation
+const MAX_FETCH_ATTEMPTS: u32 = 5;
+
+/// What a confirmed buy actually filled at
+#[derive(Debug, Clone)]
+pub struct BuyFill {
+    pub signature: String,
+    /// Raw token units received
+    pub tokens: u64,
+    /// Lamports paid for the tokens, Pump.fun protocol and creator fees included
+    pub sol_spent: u64,
+    /// Transaction fee in lamports, priority fee included
+    pub fees: u64,
+    /// Slot the buy landed in
+    pub slot: u64,
+    /// Lamports paid per raw token unit
+    pub price: f64,
+    /// Taken from the buy's quote because the transaction could not be read;
+    /// tokens and cost are what was asked for, not what was received
+    pub estimated: bool,
+}
+
+impl BuyFill {
+    pub fn new(signature: String, tokens: u64, sol_spent: u64, fees: u64, slot: u64) -> Self {
+        let price = if tokens == 0 {
+            0.0
+        } else {
+            sol_spent as f64 / tokens as f64
+        };
+
+        Self {
+            signature,
+            tokens,
+            sol_spent,
+            fees,
+            slot,
+            price,
+            estimated: false,
+        }
+    }
+
+    /// Stand-in for a landed buy whose transaction could not be read
+    pub fn estimate(signature: String, tokens: u64, sol_spent: u64) -> Self {
+        Self {
+            estimated: true,
+            ..Self::new(signature, tokens, sol_spent, 0, 0)
+        }
+    }
+}
+
+/// Fetch a confirmed buy of `mint` by `user` and read its fill
+pub async fn fetch_buy_fill(
+    client: &RpcClient,
+    signature: &str,
+    user: &Pubkey,
+    mint: &Pubkey,
+) -> Result<BuyFill> {
+    let parsed = Signature::from_str(signature).context("Invalid transaction signature")?;
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut attempt = 0;
+    let tx = loop {
+        attempt += 1;
+        match client.get_transaction_with_config(&parsed, config).await {
+            Ok(tx) => break tx,
+            Err(e) if attempt < MAX_FETCH_ATTEMPTS => {
+                log::debug!("get_transaction {} failed (attempt {}): {}", signature, attempt, e);
+                sleep(Duration::from_millis(200 * attempt as u64)).await;
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to fetch buy {}", signature)),
+        }
+    };
+
+    parse_buy_fill(&tx, signature, user, mint)
+        .with_context(|| format!("No buy of {} found in {}", mint, signature))
+}
+
+/// Read a buy's fill from its `TradeEvent` and the wallet's balance changes
+///
+/// Tokens received come from the wallet's token balance delta and the SOL
+/// paid from the event, which splits out the Pump.fun fees. Either one
+/// stands in for the other when missing; without the event, the SOL paid is
+/// everything the fee payer spent beyond the transaction fee.
+pub fn parse_buy_fill(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    signature: &str,
+    user: &Pubkey,
+    mint: &Pubkey,
+) -> Option<BuyFill> {
+    let meta = tx.transaction.meta.as_ref()?;
+    if meta.err.is_some() {
+        return None;
+    }
+
+    let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID).ok()?;
+    let trade = trade_events(tx, meta, &program_id)
+        .into_iter()
+        .find(|trade| trade.is_buy && trade.user == *user && trade.mint == *mint);
+    let token_delta = token_balance_delta(meta, user, mint);
+
+    let tokens = match (token_delta, &trade) {
+        (Some(delta), Some(trade)) => {
+            if delta != trade.token_amount {
+                log::warn!(
+                    "Token balance of {} rose by {} but the trade reports {}",
+                    mint,
+                    delta,
+                    trade.token_amount
+                );
+            }
+            delta
+        }
+        (Some(delta), None) => delta,
+        (None, Some(trade)) => trade.token_amount,
+        (None, None) => return None,
+    };
+
+    let sol_spent = match &trade {
+        Some(trade) => trade.sol_amount + trade.fee + trade.creator_fee,
+        None => {
+            let spent = meta.pre_balances.first()?.saturating_sub(*meta.post_balances.first()?);
+            spent.saturating_sub(meta.fee)
+        }
+    };
+
+    Some(BuyFill::new(signature.to_string(), tokens, sol_spent, meta.fee, tx.slot))
+}
+
+/// Trade events from both self-CPI inner instructions and log messages
+fn trade_events(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    meta: &UiTransactionStatusMeta,
+    program_id: &Pubkey,
+) -> Vec<TradeEvent> {
+    let mut found = Vec::new();
+
+    if let Some(transaction) = tx.transaction.transaction.decode() {
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
+        if let Some(loaded) = Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()) {
+            account_keys.extend(
+                loaded
+                    .writable
+                    .iter()
+                    .chain(loaded.readonly.iter())
+                    .filter_map(|key| Pubkey::from_str(key).ok()),
+            );
+        }
+
+        let inner = Option::<Vec<UiInnerInstructions>>::from(meta.inner_instructions.clone()).unwrap_or_default();
+        for ix in inner.iter().flat_map(|inner| inner.instructions.iter()) {
+            let UiInstruction::Compiled(ix) = ix else {
+                continue;
+            };
+            if account_keys.get(ix.program_id_index as usize) != Some(program_id) {
+                continue;
+            }
+            let data = bs58::decode(&ix.data).into_vec().unwrap_or_default();
+            if let Some(PumpEvent::Trade(trade)) = events::decode_cpi_event(&data) {
+                found.push(trade);
+            }
+        }
+    }
+
+    let logs = Option::<Vec<String>>::from(meta.log_messages.clone()).unwrap_or_default();
+    found.extend(
+        events::parse_logs(&logs, program_id)
+            .into_iter()
+            .filter_map(|event| match event {
+                PumpEvent::Trade(trade) => Some(trade),
+                _ => None,
+            }),
+    );
+
+    found
+}
+
+/// Raw units of `mint` gained by `owner` in the transaction
+///
+/// A token account created by the transaction itself has no pre-balance
+/// entry and counts as starting from zero.
+fn token_balance_delta(meta: &UiTransactionStatusMeta, owner: &Pubkey, mint: &Pubkey) -> Option<u64> {
+    let owner = owner.to_string();
+    let mint = mint.to_string();
+    let balance_of = |balances: Option<Vec<UiTransactionTokenBalance>>| {
+        balances?
+            .iter()
+            .find(|balance| {
+                balance.mint == mint && Option::<String>::from(balance.owner.clone()).as_deref() == Some(owner.as_str())
+            })
+            .and_then(|balance| u64::from_str(&balance.ui_token_amount.amount).ok())
+    };
+
+    let post = balance_of(meta.post_token_balances.clone().into())?;
+    let pre = balance_of(meta.pre_token_balances.clone().into()).unwrap_or(0);
+    post.checked_sub(pre)
+}
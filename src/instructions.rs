@@ -1,12 +1,19 @@
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
+    pubkey,
     pubkey::Pubkey,
     system_program,
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::config::PUMPFUN_PROGRAM_ID;
+use crate::config::{PUMPFUN_FEE_PROGRAM_ID, PUMPFUN_PROGRAM_ID, PUMP_AMM_PROGRAM_ID};
+use crate::pda;
+use crate::pump_amm::Pool;
+use crate::utils::BorshReader;
 
 /// Pump.fun instruction discriminators
 pub mod discriminators {
@@ -15,43 +22,66 @@ pub mod discriminators {
     /// Create token instruction discriminator
     pub const CREATE: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
     
-    /// Buy instruction discriminator (approximate - verify with IDL)
+    /// Buy instruction discriminator
     pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+    /// Sell instruction discriminator
+    pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
 }
 
+/// Pump.fun IDL bundled with the bot, used to validate the builders
+pub const PUMP_IDL: &str = include_str!("../idl/pump.json");
+
+/// PumpSwap AMM IDL bundled with the bot, used to validate the builders
+pub const PUMP_AMM_IDL: &str = include_str!("../idl/pump_amm.json");
+
 /// Build a Pump.fun buy instruction
 /// 
-/// This constructs a buy instruction for the bonding curve.
-/// Note: The exact instruction format may vary. This is a simplified version.
-/// In production, you should use the Anchor IDL or verify the exact instruction format.
+/// Buys exactly `token_amount` tokens, failing on-chain if that would cost
+/// more than `max_sol_cost` lamports (fees included). `creator` is the
+/// bonding curve's creator, whose vault receives the creator fee.
+/// Account order and argument layout follow the bundled IDL; see
+/// [`validate_against_idl`].
 pub fn build_buy_instruction(
-    buyer: &Pubkey,
-    bonding_curve: &Pubkey,
-    associated_bonding_curve: &Pubkey,
+    user: &Pubkey,
     mint: &Pubkey,
-    sol_reserves: &Pubkey,
-    token_mint: &Pubkey,
-    buy_amount_lamports: u64,
-    min_tokens_out: u64,
+    creator: &Pubkey,
+    fee_recipient: &Pubkey,
+    token_program: &Pubkey,
+    token_amount: u64,
+    max_sol_cost: u64,
 ) -> Result<Instruction> {
     let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
         .context("Failed to parse Pump.fun program ID")?;
+    let fee_program_id = Pubkey::from_str(PUMPFUN_FEE_PROGRAM_ID)
+        .context("Failed to parse Pump.fun fee program ID")?;
 
-    // Build instruction data: discriminator + amount + min_out
-    let mut data = Vec::new();
+    // Build instruction data: discriminator + amount + max_sol_cost + track_volume
+    let mut data = Vec::with_capacity(8 + 8 + 8 + 1);
     data.extend_from_slice(&discriminators::BUY);
-    data.extend_from_slice(&buy_amount_lamports.to_le_bytes());
-    data.extend_from_slice(&min_tokens_out.to_le_bytes());
+    data.extend_from_slice(&token_amount.to_le_bytes());
+    data.extend_from_slice(&max_sol_cost.to_le_bytes());
+    data.push(0); // track_volume: OptionBool(false)
+
+    let bonding_curve = pda::bonding_curve(&program_id, mint);
 
-    // Account metas (order matters - verify with IDL)
     let accounts = vec![
-        AccountMeta::new(*buyer, true),                    // Signer
-        AccountMeta::new(*bonding_curve, false),            // Bonding curve account
-        AccountMeta::new(*associated_bonding_curve, false), // Associated bonding curve
-        AccountMeta::new(*mint, false),                     // Token mint
-        AccountMeta::new(*sol_reserves, false),             // SOL reserves
-        AccountMeta::new(*token_mint, false),               // Token mint (duplicate?)
-        AccountMeta::new_readonly(system_program::id(), false), // System program
+        AccountMeta::new_readonly(pda::global(&program_id), false),
+        AccountMeta::new(*fee_recipient, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(pda::associated_bonding_curve(&bonding_curve, mint, token_program), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, mint, token_program), false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new(pda::creator_vault(&program_id, creator), false),
+        AccountMeta::new_readonly(pda::event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new(pda::global_volume_accumulator(&program_id), false),
+        AccountMeta::new(pda::user_volume_accumulator(&program_id, user), false),
+        AccountMeta::new_readonly(pda::fee_config(&fee_program_id, &program_id), false),
+        AccountMeta::new_readonly(fee_program_id, false),
     ];
 
     Ok(Instruction {
@@ -61,67 +91,476 @@ pub fn build_buy_instruction(
     })
 }
 
-/// Parse Create instruction from transaction data
-/// 
-/// Extracts token information from a Pump.fun Create instruction
-pub fn parse_create_instruction(data: &[u8]) -> Option<CreateInstructionData> {
-    if data.len() < 8 {
-        return None;
+/// Build a Pump.fun sell instruction
+///
+/// Sells exactly `token_amount` tokens, failing on-chain if the proceeds
+/// after fees would be less than `min_sol_output` lamports. Note that the
+/// program orders `creator_vault` before `token_program` here, unlike buy.
+pub fn build_sell_instruction(
+    user: &Pubkey,
+    mint: &Pubkey,
+    creator: &Pubkey,
+    fee_recipient: &Pubkey,
+    token_program: &Pubkey,
+    token_amount: u64,
+    min_sol_output: u64,
+) -> Result<Instruction> {
+    let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
+        .context("Failed to parse Pump.fun program ID")?;
+    let fee_program_id = Pubkey::from_str(PUMPFUN_FEE_PROGRAM_ID)
+        .context("Failed to parse Pump.fun fee program ID")?;
+
+    // Build instruction data: discriminator + amount + min_sol_output
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&discriminators::SELL);
+    data.extend_from_slice(&token_amount.to_le_bytes());
+    data.extend_from_slice(&min_sol_output.to_le_bytes());
+
+    let bonding_curve = pda::bonding_curve(&program_id, mint);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(pda::global(&program_id), false),
+        AccountMeta::new(*fee_recipient, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(pda::associated_bonding_curve(&bonding_curve, mint, token_program), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, mint, token_program), false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(pda::creator_vault(&program_id, creator), false),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new_readonly(pda::event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new_readonly(pda::fee_config(&fee_program_id, &program_id), false),
+        AccountMeta::new_readonly(fee_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Build a PumpSwap sell instruction for a migrated token
+///
+/// Sells exactly `base_amount_in` tokens into `pool`, failing on-chain if
+/// fewer than `min_quote_amount_out` quote lamports would come out. The
+/// quote side is received into the user's wrapped SOL account, which must
+/// exist. PumpSwap's sell shares its Anchor discriminator with Pump.fun's.
+pub fn build_amm_sell_instruction(
+    user: &Pubkey,
+    pool_address: &Pubkey,
+    pool: &Pool,
+    protocol_fee_recipient: &Pubkey,
+    base_token_program: &Pubkey,
+    base_amount_in: u64,
+    min_quote_amount_out: u64,
+) -> Result<Instruction> {
+    let amm_program_id = Pubkey::from_str(PUMP_AMM_PROGRAM_ID)
+        .context("Failed to parse PumpSwap program ID")?;
+    let fee_program_id = Pubkey::from_str(PUMPFUN_FEE_PROGRAM_ID)
+        .context("Failed to parse Pump.fun fee program ID")?;
+    let quote_token_program = spl_token::ID;
+
+    // Build instruction data: discriminator + base_amount_in + min_quote_amount_out
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&discriminators::SELL);
+    data.extend_from_slice(&base_amount_in.to_le_bytes());
+    data.extend_from_slice(&min_quote_amount_out.to_le_bytes());
+
+    let coin_creator_vault_authority = pda::coin_creator_vault_authority(&amm_program_id, &pool.coin_creator);
+
+    let accounts = vec![
+        AccountMeta::new(*pool_address, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(pda::amm_global_config(&amm_program_id), false),
+        AccountMeta::new_readonly(pool.base_mint, false),
+        AccountMeta::new_readonly(pool.quote_mint, false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, &pool.base_mint, base_token_program), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, &pool.quote_mint, &quote_token_program), false),
+        AccountMeta::new(pool.pool_base_token_account, false),
+        AccountMeta::new(pool.pool_quote_token_account, false),
+        AccountMeta::new_readonly(*protocol_fee_recipient, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(protocol_fee_recipient, &pool.quote_mint, &quote_token_program),
+            false,
+        ),
+        AccountMeta::new_readonly(*base_token_program, false),
+        AccountMeta::new_readonly(quote_token_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        AccountMeta::new_readonly(pda::event_authority(&amm_program_id), false),
+        AccountMeta::new_readonly(amm_program_id, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(&coin_creator_vault_authority, &pool.quote_mint, &quote_token_program),
+            false,
+        ),
+        AccountMeta::new_readonly(coin_creator_vault_authority, false),
+        AccountMeta::new_readonly(pda::fee_config(&fee_program_id, &amm_program_id), false),
+        AccountMeta::new_readonly(fee_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id: amm_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Parts of the Anchor IDL format needed to check built instructions
+#[derive(Debug, Deserialize)]
+struct Idl {
+    address: String,
+    instructions: Vec<IdlInstruction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlInstruction {
+    name: String,
+    discriminator: Vec<u8>,
+    accounts: Vec<IdlAccount>,
+    args: Vec<IdlArg>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlAccount {
+    name: String,
+    #[serde(default)]
+    writable: bool,
+    #[serde(default)]
+    signer: bool,
+    /// Fixed address the account must have
+    address: Option<String>,
+    /// Seeds the account is derived from
+    pda: Option<IdlPda>,
+    /// Accounts whose data stores this account's address, under its name
+    #[serde(default)]
+    relations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlPda {
+    seeds: Vec<IdlSeed>,
+    /// Program the PDA belongs to, when not the IDL's own
+    program: Option<IdlSeed>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum IdlSeed {
+    Const { value: Vec<u8> },
+    Account { path: String },
+    Arg { path: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlArg {
+    name: String,
+    #[serde(rename = "type")]
+    ty: serde_json::Value,
+}
+
+impl IdlArg {
+    /// Encoded size of fixed-size argument types
+    fn fixed_size(&self) -> Option<usize> {
+        match &self.ty {
+            serde_json::Value::String(ty) => match ty.as_str() {
+                "u8" | "bool" => Some(1),
+                "u64" | "i64" => Some(8),
+                "pubkey" => Some(32),
+                _ => None,
+            },
+            ty if ty["defined"]["name"] == "OptionBool" => Some(1),
+            _ => None,
+        }
     }
+}
 
-    // Check discriminator
-    let discriminator = &data[0..8];
-    if discriminator != discriminators::CREATE {
-        return None;
+/// Accounts of a built instruction by IDL name, plus the account data
+/// fields (`account.field`) its seeds and relations refer to
+struct ResolvedAccounts<'a> {
+    accounts: HashMap<&'a str, Pubkey>,
+    fields: &'a [(&'a str, Pubkey)],
+}
+
+impl ResolvedAccounts<'_> {
+    fn get(&self, path: &str) -> Result<Pubkey> {
+        self.accounts
+            .get(path)
+            .copied()
+            .or_else(|| self.fields.iter().find(|(field, _)| *field == path).map(|(_, key)| *key))
+            .with_context(|| format!("no value for `{}`", path))
     }
 
-    // Parse instruction data
-    // Note: This is a simplified parser. The actual format may vary.
-    // You should verify with the Pump.fun IDL or program source.
-    if data.len() < 8 + 32 * 4 {
-        return None;
+    fn seed(&self, seed: &IdlSeed) -> Result<Vec<u8>> {
+        match seed {
+            IdlSeed::Const { value } => Ok(value.clone()),
+            IdlSeed::Account { path } => Ok(self.get(path)?.to_bytes().to_vec()),
+            IdlSeed::Arg { path } => anyhow::bail!("argument seed `{}` is not supported", path),
+        }
     }
+}
+
+/// Check the instruction builders against the bundled Pump.fun and
+/// PumpSwap IDLs
+///
+/// Builds sample instructions and compares discriminator, account order,
+/// writability, signer flags and argument layout, and checks every account
+/// with a fixed address, PDA seeds or a relation in the IDL against what
+/// the builder put there, so a builder that drifts from the IDL fails at
+/// startup instead of on-chain.
+pub fn validate_against_idl() -> Result<()> {
+    let idl: Idl = serde_json::from_str(PUMP_IDL).context("Failed to parse bundled Pump.fun IDL")?;
+    let creator = Pubkey::new_unique();
+
+    // Token-2022 mints derive their token accounts differently
+    for token_program in [spl_token::ID, pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb")] {
+        let buy_ix = build_buy_instruction(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &creator,
+            &Pubkey::new_unique(),
+            &token_program,
+            1,
+            1,
+        )?;
+
+        validate_instruction(&idl, "buy", &buy_ix, &[("bonding_curve.creator", creator)])?;
+
+        let sell_ix = build_sell_instruction(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &creator,
+            &Pubkey::new_unique(),
+            &token_program,
+            1,
+            1,
+        )?;
+
+        validate_instruction(&idl, "sell", &sell_ix, &[("bonding_curve.creator", creator)])?;
+    }
+
+    let amm_idl: Idl = serde_json::from_str(PUMP_AMM_IDL).context("Failed to parse bundled PumpSwap IDL")?;
+
+    let pool_address = Pubkey::new_unique();
+    let base_mint = Pubkey::new_unique();
+    let quote_mint = spl_token::native_mint::ID;
+    let pool = Pool {
+        base_mint,
+        quote_mint,
+        pool_base_token_account: get_associated_token_address_with_program_id(&pool_address, &base_mint, &spl_token::ID),
+        pool_quote_token_account: get_associated_token_address_with_program_id(&pool_address, &quote_mint, &spl_token::ID),
+        coin_creator: creator,
+    };
+    let amm_sell_ix = build_amm_sell_instruction(
+        &Pubkey::new_unique(),
+        &pool_address,
+        &pool,
+        &Pubkey::new_unique(),
+        &spl_token::ID,
+        1,
+        1,
+    )?;
+
+    validate_instruction(
+        &amm_idl,
+        "sell",
+        &amm_sell_ix,
+        &[
+            ("pool.base_mint", pool.base_mint),
+            ("pool.quote_mint", pool.quote_mint),
+            ("pool.pool_base_token_account", pool.pool_base_token_account),
+            ("pool.pool_quote_token_account", pool.pool_quote_token_account),
+            ("pool.coin_creator", pool.coin_creator),
+        ],
+    )
+}
+
+/// Compare one built instruction against its IDL definition
+///
+/// `fields` gives the account data fields the IDL's seeds and relations
+/// refer to, as the builder was given them.
+fn validate_instruction(idl: &Idl, name: &str, instruction: &Instruction, fields: &[(&str, Pubkey)]) -> Result<()> {
+    let program_id = Pubkey::from_str(&idl.address).context("Invalid IDL program address")?;
+    let definition = idl
+        .instructions
+        .iter()
+        .find(|ix| ix.name == name)
+        .with_context(|| format!("Instruction `{}` missing from IDL", name))?;
+
+    if instruction.program_id != program_id {
+        anyhow::bail!("`{}` targets {}, IDL program is {}", name, instruction.program_id, program_id);
+    }
+
+    if !instruction.data.starts_with(&definition.discriminator) {
+        anyhow::bail!("`{}` discriminator does not match the IDL", name);
+    }
+
+    if definition.accounts.len() != instruction.accounts.len() {
+        anyhow::bail!(
+            "`{}` has {} accounts, IDL expects {}",
+            name,
+            instruction.accounts.len(),
+            definition.accounts.len()
+        );
+    }
+
+    let resolved = ResolvedAccounts {
+        accounts: definition
+            .accounts
+            .iter()
+            .zip(&instruction.accounts)
+            .map(|(expected, meta)| (expected.name.as_str(), meta.pubkey))
+            .collect(),
+        fields,
+    };
+
+    for (expected, meta) in definition.accounts.iter().zip(&instruction.accounts) {
+        if expected.writable != meta.is_writable || expected.signer != meta.is_signer {
+            anyhow::bail!(
+                "`{}` account `{}` has writable={}, signer={}; IDL expects writable={}, signer={}",
+                name,
+                expected.name,
+                meta.is_writable,
+                meta.is_signer,
+                expected.writable,
+                expected.signer
+            );
+        }
+
+        let address = match (&expected.address, &expected.pda) {
+            (Some(address), _) => Some(
+                Pubkey::from_str(address)
+                    .with_context(|| format!("`{}` account `{}` has an invalid IDL address", name, expected.name))?,
+            ),
+            (None, Some(pda)) => Some(
+                derive_pda(pda, &program_id, &resolved)
+                    .with_context(|| format!("Cannot derive `{}` account `{}`", name, expected.name))?,
+            ),
+            (None, None) => None,
+        };
+        if let Some(address) = address {
+            if address != meta.pubkey {
+                anyhow::bail!(
+                    "`{}` account `{}` is {}, IDL expects {}",
+                    name,
+                    expected.name,
+                    meta.pubkey,
+                    address
+                );
+            }
+        }
+
+        for relation in &expected.relations {
+            let stored = resolved
+                .get(&format!("{}.{}", relation, expected.name))
+                .with_context(|| format!("Cannot check `{}` account `{}`", name, expected.name))?;
+            if stored != meta.pubkey {
+                anyhow::bail!(
+                    "`{}` account `{}` is {}, but `{}` stores {}",
+                    name,
+                    expected.name,
+                    meta.pubkey,
+                    relation,
+                    stored
+                );
+            }
+        }
+    }
+
+    let args_len = definition
+        .args
+        .iter()
+        .map(|arg| {
+            arg.fixed_size()
+                .with_context(|| format!("`{}` argument `{}` is not fixed-size", name, arg.name))
+        })
+        .sum::<Result<usize>>()?;
+
+    if instruction.data.len() != definition.discriminator.len() + args_len {
+        anyhow::bail!(
+            "`{}` data is {} bytes, IDL layout is {} bytes",
+            name,
+            instruction.data.len(),
+            definition.discriminator.len() + args_len
+        );
+    }
+
+    Ok(())
+}
+
+/// Derive the address an IDL `pda` describes
+fn derive_pda(pda: &IdlPda, program_id: &Pubkey, resolved: &ResolvedAccounts) -> Result<Pubkey> {
+    let seeds = pda
+        .seeds
+        .iter()
+        .map(|seed| resolved.seed(seed))
+        .collect::<Result<Vec<_>>>()?;
+    let program_id = match &pda.program {
+        Some(program) => Pubkey::try_from(resolved.seed(program)?.as_slice())
+            .map_err(|_| anyhow::anyhow!("PDA program is not an address"))?,
+        None => *program_id,
+    };
+
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    Ok(Pubkey::find_program_address(&seeds, &program_id).0)
+}
+
+/// Parse Create instruction from transaction data
+/// 
+/// Decodes the Anchor arguments `(name, symbol, uri, creator)`. Creates sent
+/// before the program added the `creator` argument decode with `creator: None`.
+pub fn parse_create_instruction(data: &[u8]) -> Option<CreateInstructionData> {
+    let args = data.strip_prefix(&discriminators::CREATE)?;
+    let mut reader = BorshReader::new(args);
+
+    let name = reader.read_string()?;
+    let symbol = reader.read_string()?;
+    let uri = reader.read_string()?;
+    let creator = if reader.is_empty() {
+        None
+    } else {
+        Some(reader.read_pubkey()?)
+    };
 
-    // Extract pubkeys (assuming they're in the instruction data)
-    // In reality, these are likely in the account metas
-    let mut offset = 8;
-    
-    // This is a placeholder - actual parsing depends on instruction format
-    // You'll need to extract from account metas in the transaction
     Some(CreateInstructionData {
-        // These would be extracted from account metas
-        mint: None,
-        bonding_curve: None,
-        creator: None,
+        name,
+        symbol,
+        uri,
+        creator,
     })
 }
 
-/// Data extracted from a Create instruction
+/// Arguments of a Create instruction
 #[derive(Debug, Clone)]
 pub struct CreateInstructionData {
-    pub mint: Option<Pubkey>,
-    pub bonding_curve: Option<Pubkey>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
     pub creator: Option<Pubkey>,
 }
 
 /// Extract token creation data from transaction accounts
 /// 
-/// This is a helper to extract relevant accounts from a transaction
-/// that contains a Create instruction
+/// `accounts` is the transaction's full account key list (including any
+/// lookup-table addresses) and `instruction_account_indices` the Create
+/// instruction's indices into it. Account order follows the Pump.fun IDL:
+/// mint, mint_authority, bonding_curve, associated_bonding_curve, global,
+/// mpl_token_metadata, metadata, user, system_program, token_program, ...
 pub fn extract_create_accounts(
     accounts: &[Pubkey],
     instruction_account_indices: &[u8],
 ) -> Option<CreateAccounts> {
-    // The account order depends on the instruction format
-    // This is a simplified version - verify with IDL
-    if instruction_account_indices.len() < 4 {
+    if instruction_account_indices.len() < 10 {
         return None;
     }
 
     Some(CreateAccounts {
-        mint: accounts.get(instruction_account_indices[0] as usize)?.clone(),
-        bonding_curve: accounts.get(instruction_account_indices[1] as usize)?.clone(),
-        creator: accounts.get(instruction_account_indices[2] as usize)?.clone(),
+        mint: *accounts.get(instruction_account_indices[0] as usize)?,
+        bonding_curve: *accounts.get(instruction_account_indices[2] as usize)?,
+        creator: *accounts.get(instruction_account_indices[7] as usize)?,
+        token_program: *accounts.get(instruction_account_indices[9] as usize)?,
     })
 }
 
@@ -131,4 +570,68 @@ pub struct CreateAccounts {
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub creator: Pubkey,
+    pub token_program: Pubkey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pump_idl() -> Idl {
+        serde_json::from_str(PUMP_IDL).unwrap()
+    }
+
+    fn buy(creator: &Pubkey, token_program: &Pubkey) -> Instruction {
+        build_buy_instruction(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            creator,
+            &Pubkey::new_unique(),
+            token_program,
+            1,
+            1,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn builders_match_bundled_idls() {
+        validate_against_idl().unwrap();
+    }
+
+    #[test]
+    fn rejects_account_not_derived_from_idl_seeds() {
+        let creator = Pubkey::new_unique();
+        let error = validate_instruction(
+            &pump_idl(),
+            "buy",
+            &buy(&creator, &spl_token::ID),
+            &[("bonding_curve.creator", Pubkey::new_unique())],
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("creator_vault"), "{:#}", error);
+    }
+
+    #[test]
+    fn rejects_wrong_fixed_address() {
+        let creator = Pubkey::new_unique();
+        let mut ix = buy(&creator, &spl_token::ID);
+        // system_program
+        ix.accounts[7].pubkey = Pubkey::new_unique();
+
+        let error = validate_instruction(&pump_idl(), "buy", &ix, &[("bonding_curve.creator", creator)]).unwrap_err();
+        assert!(error.to_string().contains("system_program"), "{:#}", error);
+    }
+
+    #[test]
+    fn rejects_token_accounts_of_another_token_program() {
+        let creator = Pubkey::new_unique();
+        let mut ix = buy(&creator, &spl_token::ID);
+        // token_program, leaving the token accounts derived for spl-token
+        ix.accounts[8].pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+        let error = validate_instruction(&pump_idl(), "buy", &ix, &[("bonding_curve.creator", creator)]).unwrap_err();
+        assert!(error.to_string().contains("associated_bonding_curve"), "{:#}", error);
+    }
 }
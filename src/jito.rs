@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::{
+    instruction::Instruction, pubkey, pubkey::Pubkey, system_instruction,
+    transaction::VersionedTransaction,
+};
+
+/// Default mainnet block engine
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// Mainnet Jito tip accounts; one is picked at random per bundle
+pub const TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatuses {
+    #[serde(default)]
+    value: Vec<Option<BundleStatus>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatus {
+    slot: u64,
+}
+
+/// Client for the Jito block engine bundle JSON-RPC API
+pub struct JitoClient {
+    http: reqwest::Client,
+    bundles_url: String,
+    tip_lamports: u64,
+}
+
+impl JitoClient {
+    pub fn new(block_engine_url: &str, tip_lamports: u64) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            bundles_url: format!("{}/api/v1/bundles", block_engine_url.trim_end_matches('/')),
+            tip_lamports,
+        }
+    }
+
+    /// Tip transfer to a randomly chosen tip account
+    pub fn tip_instruction(&self, payer: &Pubkey) -> Instruction {
+        let tip_account = TIP_ACCOUNTS
+            .choose(&mut rand::thread_rng())
+            .copied()
+            .unwrap_or(TIP_ACCOUNTS[0]);
+
+        system_instruction::transfer(payer, &tip_account, self.tip_lamports)
+    }
+
+    /// Submit signed transactions as a bundle, returning the bundle ID
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        let encoded = transactions
+            .iter()
+            .map(|tx| {
+                bincode::serialize(tx)
+                    .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+                    .context("Failed to serialize bundle transaction")
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.call("sendBundle", json!([encoded, { "encoding": "base64" }]))
+            .await
+    }
+
+    /// Slot a bundle landed in, or `None` if the block engine has not seen it land
+    pub async fn bundle_landed(&self, bundle_id: &str) -> Result<Option<u64>> {
+        let statuses: BundleStatuses = self
+            .call("getBundleStatuses", json!([[bundle_id]]))
+            .await?;
+
+        Ok(statuses
+            .value
+            .into_iter()
+            .next()
+            .flatten()
+            .map(|status| status.slot))
+    }
+
+    /// Make a JSON-RPC call against the block engine
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let response: JsonRpcResponse<T> = self
+            .http
+            .post(&self.bundles_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach Jito block engine for {}", method))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid {} response from Jito block engine", method))?;
+
+        if let Some(error) = response.error {
+            anyhow::bail!("Jito {} rejected ({}): {}", method, error.code, error.message);
+        }
+
+        response
+            .result
+            .with_context(|| format!("Jito {} returned no result", method))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use solana_sdk::{
+        hash::Hash, message::Message, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Serve one canned JSON-RPC result per request, passing each request body on
+    async fn mock_block_engine(responses: Vec<Value>) -> (String, mpsc::UnboundedReceiver<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (requests_tx, requests) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut socket).await;
+                let _ = requests_tx.send(serde_json::from_slice(&request).unwrap());
+
+                let body = response.to_string();
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    /// Read an HTTP request and return its body
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+
+        loop {
+            let read = socket.read(&mut chunk).await.unwrap();
+            buffer.extend_from_slice(&chunk[..read]);
+
+            if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                let headers = String::from_utf8_lossy(&buffer[..end]).to_lowercase();
+                let length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse().unwrap())
+                    .unwrap_or(0);
+                if buffer.len() >= end + 4 + length {
+                    return buffer[end + 4..end + 4 + length].to_vec();
+                }
+            }
+            if read == 0 {
+                panic!("connection closed before the request was complete");
+            }
+        }
+    }
+
+    fn signed_transaction() -> VersionedTransaction {
+        let payer = Keypair::new();
+        let client = JitoClient::new(DEFAULT_BLOCK_ENGINE_URL, 10_000);
+        let message = Message::new(&[client.tip_instruction(&payer.pubkey())], Some(&payer.pubkey()));
+        Transaction::new(&[&payer], message, Hash::new_unique()).into()
+    }
+
+    #[tokio::test]
+    async fn sends_the_signed_transaction_unchanged() {
+        let (url, mut requests) =
+            mock_block_engine(vec![json!({ "jsonrpc": "2.0", "id": 1, "result": "bundle-1" })]).await;
+        let client = JitoClient::new(&format!("{}/", url), 10_000);
+        let transaction = signed_transaction();
+
+        let bundle_id = client.send_bundle(std::slice::from_ref(&transaction)).await.unwrap();
+        assert_eq!(bundle_id, "bundle-1");
+
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request["method"], "sendBundle");
+        assert_eq!(request["params"][1]["encoding"], "base64");
+
+        let sent = request["params"][0].as_array().unwrap();
+        assert_eq!(sent.len(), 1);
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(sent[0].as_str().unwrap())
+            .unwrap();
+        assert_eq!(bytes, bincode::serialize(&transaction).unwrap());
+    }
+
+    #[tokio::test]
+    async fn reports_bundle_landing_slot() {
+        let (url, mut requests) = mock_block_engine(vec![
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "context": { "slot": 300 }, "value": [{ "slot": 299 }] } }),
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "context": { "slot": 300 }, "value": [null] } }),
+        ])
+        .await;
+        let client = JitoClient::new(&url, 10_000);
+
+        assert_eq!(client.bundle_landed("bundle-1").await.unwrap(), Some(299));
+        assert_eq!(client.bundle_landed("bundle-2").await.unwrap(), None);
+
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request["method"], "getBundleStatuses");
+        assert_eq!(request["params"], json!([["bundle-1"]]));
+    }
+
+    #[tokio::test]
+    async fn surfaces_block_engine_errors() {
+        let (url, _requests) = mock_block_engine(vec![json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32602, "message": "bundle contains an already processed transaction" }
+        })])
+        .await;
+        let client = JitoClient::new(&url, 10_000);
+
+        let error = client.send_bundle(&[signed_transaction()]).await.unwrap_err();
+        assert!(error.to_string().contains("already processed"), "{:#}", error);
+    }
+}
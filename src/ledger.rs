@@ -0,0 +1,324 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::error::SnipeError;
+
+/// What a buy limit is counted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyLimit {
+    Mint,
+    Creator,
+}
+
+impl fmt::Display for BuyLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mint => write!(f, "mint"),
+            Self::Creator => write!(f, "creator"),
+        }
+    }
+}
+
+/// Buys of one mint or by one creator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Entry {
+    buys: u32,
+    /// Unix time of the latest buy, in seconds
+    last_buy: u64,
+}
+
+/// Buys made so far, as persisted
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Counts {
+    #[serde(default)]
+    mints: HashMap<String, Entry>,
+    #[serde(default)]
+    creators: HashMap<String, Entry>,
+    /// Bumped on every change, so writes finishing out of order are told apart
+    #[serde(skip)]
+    version: u64,
+}
+
+impl Counts {
+    fn get(&self, limit: BuyLimit, key: &Pubkey) -> u32 {
+        let counts = match limit {
+            BuyLimit::Mint => &self.mints,
+            BuyLimit::Creator => &self.creators,
+        };
+        counts.get(&key.to_string()).map_or(0, |entry| entry.buys)
+    }
+
+    fn add(&mut self, mint: &Pubkey, creator: &Pubkey) {
+        let now = unix_time();
+        for (counts, key) in [(&mut self.mints, mint), (&mut self.creators, creator)] {
+            let entry = counts.entry(key.to_string()).or_insert(Entry { buys: 0, last_buy: now });
+            entry.buys += 1;
+            entry.last_buy = now;
+        }
+    }
+
+    fn remove(&mut self, mint: &Pubkey, creator: &Pubkey) {
+        for (counts, key) in [(&mut self.mints, mint), (&mut self.creators, creator)] {
+            let key = key.to_string();
+            if let Some(entry) = counts.get_mut(&key) {
+                entry.buys = entry.buys.saturating_sub(1);
+                if entry.buys == 0 {
+                    counts.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Forget mints and creators not bought within `retention`
+    fn prune(&mut self, retention: Duration) {
+        let cutoff = unix_time().saturating_sub(retention.as_secs());
+        for counts in [&mut self.mints, &mut self.creators] {
+            counts.retain(|_, entry| entry.last_buy >= cutoff);
+        }
+    }
+
+    /// Serialize the current counts, tagged with the version they were taken at
+    fn snapshot(&mut self, retention: Option<Duration>) -> Result<(u64, String)> {
+        if let Some(retention) = retention {
+            self.prune(retention);
+        }
+        self.version += 1;
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize buy ledger")?;
+        Ok((self.version, contents))
+    }
+}
+
+/// Buys per mint and per creator, kept on disk across restarts
+///
+/// A buy is reserved before its transaction is signed and the reservation
+/// is written out straight away, so the same mint arriving from two sources
+/// or again after a restart is refused. Buys known not to have landed
+/// release their reservation. Mints and creators not bought within
+/// `LEDGER_RETENTION_HOURS` are forgotten.
+///
+/// Writes run on the blocking thread pool, one at a time; a write whose
+/// snapshot is older than the one already on disk is skipped.
+pub struct BuyLedger {
+    path: PathBuf,
+    /// Buys allowed per mint (0 for no limit)
+    max_per_mint: u32,
+    /// Buys allowed per creator (0 for no limit)
+    max_per_creator: u32,
+    /// How long buys are remembered, or forever if `None`
+    retention: Option<Duration>,
+    counts: Mutex<Counts>,
+    /// Version of the snapshot last written to disk
+    written: tokio::sync::Mutex<u64>,
+}
+
+impl BuyLedger {
+    /// Load the ledger from `LEDGER_PATH`, starting empty if the file does not exist
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = PathBuf::from(&config.ledger_path);
+        let retention = (config.ledger_retention_hours > 0)
+            .then(|| Duration::from_secs(config.ledger_retention_hours * 3600));
+
+        let mut counts: Counts = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read buy ledger {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid buy ledger {}", path.display()))?
+        } else {
+            Counts::default()
+        };
+        if let Some(retention) = retention {
+            counts.prune(retention);
+        }
+
+        log::info!(
+            "Buy ledger {}: {} mints and {} creators bought before",
+            path.display(),
+            counts.mints.len(),
+            counts.creators.len()
+        );
+
+        Ok(Self {
+            path,
+            max_per_mint: config.max_buys_per_mint,
+            max_per_creator: config.max_buys_per_creator,
+            retention,
+            counts: Mutex::new(counts),
+            written: tokio::sync::Mutex::new(0),
+        })
+    }
+
+    /// Reserve a buy of `mint` from `creator`, if both are under their limits
+    ///
+    /// Fails closed: if the reservation cannot be persisted, the buy is refused.
+    pub async fn reserve(&self, mint: &Pubkey, creator: &Pubkey) -> Result<(), SnipeError> {
+        let snapshot = {
+            let mut counts = self.counts.lock().unwrap();
+
+            for (limit, key, max) in [
+                (BuyLimit::Mint, mint, self.max_per_mint),
+                (BuyLimit::Creator, creator, self.max_per_creator),
+            ] {
+                let buys = counts.get(limit, key);
+                if max > 0 && buys >= max {
+                    return Err(SnipeError::BuyLimitReached { limit, key: *key, buys });
+                }
+            }
+
+            counts.add(mint, creator);
+            counts.snapshot(self.retention)
+        };
+
+        let result = match snapshot {
+            Ok((version, contents)) => self.write(version, contents).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            self.counts.lock().unwrap().remove(mint, creator);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// Give back a reservation whose buy is known not to have landed
+    pub async fn release(&self, mint: &Pubkey, creator: &Pubkey) {
+        let snapshot = {
+            let mut counts = self.counts.lock().unwrap();
+            counts.remove(mint, creator);
+            counts.snapshot(self.retention)
+        };
+
+        let result = match snapshot {
+            Ok((version, contents)) => self.write(version, contents).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to release buy of {} in the ledger: {:#}", mint, e);
+        }
+    }
+
+    /// Write a snapshot unless a newer one is already on disk
+    async fn write(&self, version: u64, contents: String) -> Result<()> {
+        let mut written = self.written.lock().await;
+        if *written >= version {
+            return Ok(());
+        }
+
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || save(&path, &contents))
+            .await
+            .context("Buy ledger write panicked")??;
+
+        *written = version;
+        Ok(())
+    }
+}
+
+/// Write the ledger through a temporary file so a crash never leaves it half written
+fn save(path: &Path, contents: &str) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+
+    std::fs::write(&tmp_path, contents)
+        .with_context(|| format!("Failed to write buy ledger {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to replace buy ledger {}", path.display()))
+}
+
+/// Seconds since the Unix epoch
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(name: &str, max_per_mint: u32, max_per_creator: u32) -> BuyLedger {
+        let path = std::env::temp_dir().join(format!("buy_ledger_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let config = Config {
+            ledger_path: path.to_string_lossy().into_owned(),
+            max_buys_per_mint: max_per_mint,
+            max_buys_per_creator: max_per_creator,
+            ..Default::default()
+        };
+        BuyLedger::load(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn refuses_buys_over_the_limits() {
+        let ledger = ledger("limits", 1, 2);
+        let creator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        ledger.reserve(&mint, &creator).await.unwrap();
+        assert!(matches!(
+            ledger.reserve(&mint, &creator).await,
+            Err(SnipeError::BuyLimitReached { limit: BuyLimit::Mint, buys: 1, .. })
+        ));
+
+        ledger.reserve(&Pubkey::new_unique(), &creator).await.unwrap();
+        assert!(matches!(
+            ledger.reserve(&Pubkey::new_unique(), &creator).await,
+            Err(SnipeError::BuyLimitReached { limit: BuyLimit::Creator, buys: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn persists_reservations_and_releases() {
+        let ledger = ledger("persist", 1, 0);
+        let creator = Pubkey::new_unique();
+        let (kept, released) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        ledger.reserve(&kept, &creator).await.unwrap();
+        ledger.reserve(&released, &creator).await.unwrap();
+        ledger.release(&released, &creator).await;
+
+        let config = Config {
+            ledger_path: ledger.path.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let reloaded = BuyLedger::load(&config).unwrap();
+        assert!(reloaded.reserve(&kept, &creator).await.is_err());
+        reloaded.reserve(&released, &creator).await.unwrap();
+
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn skips_snapshots_older_than_the_one_written() {
+        let ledger = ledger("versions", 1, 0);
+        let older = ledger.counts.lock().unwrap().snapshot(None).unwrap();
+        ledger.reserve(&Pubkey::new_unique(), &Pubkey::new_unique()).await.unwrap();
+
+        ledger.write(older.0, older.1).await.unwrap();
+        let on_disk: Counts = serde_json::from_str(&std::fs::read_to_string(&ledger.path).unwrap()).unwrap();
+        assert_eq!(on_disk.mints.len(), 1);
+
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[test]
+    fn prunes_entries_past_retention() {
+        let now = unix_time();
+        let mut counts = Counts::default();
+        counts.mints.insert("old".to_string(), Entry { buys: 1, last_buy: now - 7200 });
+        counts.mints.insert("recent".to_string(), Entry { buys: 1, last_buy: now - 60 });
+        counts.creators.insert("old".to_string(), Entry { buys: 3, last_buy: now - 7200 });
+
+        counts.prune(Duration::from_secs(3600));
+
+        assert_eq!(counts.mints.keys().collect::<Vec<_>>(), vec!["recent"]);
+        assert!(counts.creators.is_empty());
+    }
+}
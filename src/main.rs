@@ -1,17 +1,38 @@
+mod blockhash;
+mod bonding_curve;
+mod broadcast;
 mod config;
+mod confirm;
 mod detector;
+mod error;
+mod events;
+mod exit_strategy;
+mod fill;
 mod instructions;
+mod jito;
+mod ledger;
+mod pda;
+mod position;
+mod priority_fee;
+mod pump_amm;
 mod sniper;
+mod tx_builder;
 mod utils;
 mod wallet;
 
 use anyhow::{Context, Result};
+use std::sync::Arc;
 use tokio::signal;
+use tokio::sync::Semaphore;
+use tokio::time::Duration;
 use tokio_stream::StreamExt;
 
-use config::{CliArgs, Config};
-use detector::TokenDetector;
-use sniper::Sniper;
+use config::{CliArgs, Command, Config};
+use detector::{TokenCreationEvent, TokenDetector};
+use error::SnipeError;
+use ledger::BuyLedger;
+use position::PositionManager;
+use sniper::{SellAmount, Sniper};
 use utils::init_logging;
 use wallet::Wallet;
 
@@ -39,25 +60,52 @@ async fn main() -> Result<()> {
         log::info!("  Yellowstone gRPC URL: {}", grpc_url);
     }
     log::info!("  Buy Amount: {} SOL", config.buy_amount_sol);
-    log::info!("  Priority Fee: {} micro-lamports", config.priority_fee_micro_lamports);
+    log::info!(
+        "  Priority Fee: {} micro-lamports ({:?})",
+        config.priority_fee_micro_lamports,
+        config.priority_fee_strategy
+    );
     log::info!("  Min Liquidity: {} SOL", config.min_initial_liquidity_sol);
     log::info!("  Dry Run: {}", config.dry_run);
     log::info!("  Jito Enabled: {}", config.jito_enabled);
     log::info!("  Blacklisted Creators: {}", config.blacklisted_creators.len());
 
+    // Make sure the instruction builders still match the bundled IDL
+    instructions::validate_against_idl()
+        .context("Instruction builders do not match the bundled Pump.fun IDL")?;
+
     // Load wallet
     let wallet = Wallet::from_config(&config)
         .context("Failed to load wallet")?;
 
     log::info!("Wallet loaded: {}", wallet.pubkey());
 
-    // Check balance
-    let sniper = Sniper::new(
+    let ledger = BuyLedger::load(&config).context("Failed to load buy ledger")?;
+
+    let sniper = Arc::new(Sniper::new(
         config.rpc_url.clone(),
         wallet.clone(),
         config.clone(),
-    );
+        ledger,
+    ));
+
+    // One-off sell instead of the snipe loop
+    if let Some(Command::Sell { mint, amount, percent }) = cli_args.command.clone() {
+        let amount = match (amount, percent) {
+            (Some(tokens), _) => SellAmount::Tokens(tokens),
+            (None, Some(percent)) => SellAmount::Percent(percent),
+            (None, None) => anyhow::bail!("Specify --amount or --percent to sell"),
+        };
 
+        let signature = sniper
+            .execute_sell(&mint, amount)
+            .await
+            .with_context(|| format!("Failed to sell {}", mint))?;
+        log::info!("Sold {}: transaction {}", mint, signature);
+        return Ok(());
+    }
+
+    // Check balance
     let balance = sniper.get_balance().await?;
     log::info!("Wallet balance: {:.4} SOL", balance as f64 / 1_000_000_000.0);
 
@@ -67,8 +115,30 @@ async fn main() -> Result<()> {
     }
 
     // Create token detector
-    let detector = TokenDetector::new(config.clone())
-        .context("Failed to create token detector")?;
+    let detector = Arc::new(
+        TokenDetector::new(config.clone())
+            .context("Failed to create token detector")?,
+    );
+
+    // Track positions for automatic exits when any exit rule is configured
+    let positions = config
+        .exit_strategy()
+        .context("Invalid exit strategy")?
+        .map(|strategy| {
+            log::info!("Exit strategy: {:?}", strategy);
+            PositionManager::new(sniper.clone(), config.clone(), strategy)
+        });
+
+    // Sell held tokens as soon as their curve completes or migrates, and
+    // follow their curves through Geyser as well when it streams them
+    if let Some(ref positions) = positions {
+        positions.watch_completions(detector.completions());
+        if config.geyser_subscribe_bonding_curves {
+            positions.watch_curve_updates(detector.curve_updates());
+        }
+    } else if config.geyser_subscribe_bonding_curves {
+        log::warn!("GEYSER_SUBSCRIBE_BONDING_CURVES is set but no exit strategy is configured; curve updates go unused");
+    }
 
     // Setup graceful shutdown
     let shutdown_signal = async {
@@ -79,8 +149,12 @@ async fn main() -> Result<()> {
     };
 
     // Main detection and snipe loop
-    let snipe_handle = tokio::spawn(async move {
-        run_snipe_loop(detector, sniper, config).await;
+    let snipe_handle = tokio::spawn({
+        let detector = detector.clone();
+        let sniper = sniper.clone();
+        async move {
+            run_snipe_loop(detector, sniper, positions, config).await;
+        }
     });
 
     // Wait for shutdown signal or snipe loop completion
@@ -94,12 +168,23 @@ async fn main() -> Result<()> {
         }
     }
 
+    detector.log_stats();
+    sniper.broadcaster().log_stats();
+
     Ok(())
 }
 
+/// Feed detected creates through a bounded pool of concurrent snipes
+///
+/// At most `max_in_flight_snipes` creates are evaluated or bought at once.
+/// The next event is only taken off the detector channel once a slot is
+/// free, so while every slot is busy creates queue up there (and the
+/// detector waits once it is full). Creates that waited longer than
+/// `max_event_age_ms` are dropped rather than bought late.
 async fn run_snipe_loop(
-    detector: TokenDetector,
-    sniper: Sniper,
+    detector: Arc<TokenDetector>,
+    sniper: Arc<Sniper>,
+    positions: Option<Arc<PositionManager>>,
     config: Config,
 ) {
     log::info!("Starting token detection and sniping loop");
@@ -113,47 +198,88 @@ async fn run_snipe_loop(
         }
     };
 
-    log::info!("Token detection active. Waiting for new token launches...");
-
-    // Process events from stream
-    while let Some(event) = event_stream.next().await {
-        log::info!(
-            "New token detected: mint={}, creator={}, signature={}",
-            event.mint,
-            event.creator,
-            event.signature
-        );
-
-        // Evaluate token against filters
-        match sniper.evaluate_token(&event).await {
-            Ok(should_snipe) => {
-                if should_snipe {
-                    log::info!("Token passed filters. Executing buy...");
-                    
-                    match sniper.execute_buy(&event).await {
-                        Ok(tx_sig) => {
-                            log::info!(
-                                "Successfully sniped token {}: transaction {}",
-                                event.mint,
-                                tx_sig
-                            );
-                        }
-                        Err(e) => {
-                            log::error!("Failed to execute buy for {}: {}", event.mint, e);
-                        }
+    log::info!(
+        "Token detection active. Waiting for new token launches ({} snipes at a time)...",
+        config.max_in_flight_snipes
+    );
+
+    let in_flight = Arc::new(Semaphore::new(config.max_in_flight_snipes));
+    let max_event_age = Duration::from_millis(config.max_event_age_ms);
+
+    loop {
+        if in_flight.available_permits() == 0 {
+            log::debug!("All {} snipe slots busy; holding new events", config.max_in_flight_snipes);
+        }
+        let permit = match in_flight.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => break,
+        };
+
+        let Some(event) = event_stream.next().await else {
+            log::warn!("Event stream ended unexpectedly");
+            return;
+        };
+
+        let age = event.detected_at.elapsed();
+        if config.max_event_age_ms > 0 && age > max_event_age {
+            log::info!("Dropping stale create for {}: detected {}ms ago", event.mint, age.as_millis());
+            continue;
+        }
+
+        let sniper = sniper.clone();
+        let positions = positions.clone();
+        tokio::spawn(async move {
+            snipe(&event, &sniper, positions.as_ref()).await;
+            drop(permit);
+        });
+    }
+}
+
+/// Evaluate a detected create and buy it if it passes the filters
+async fn snipe(event: &TokenCreationEvent, sniper: &Sniper, positions: Option<&Arc<PositionManager>>) {
+    log::info!(
+        "New token detected: mint={}, name={}, symbol={}, creator={}, signature={}",
+        event.mint,
+        event.name,
+        event.symbol,
+        event.creator,
+        event.signature
+    );
+
+    // Evaluate token against filters
+    match sniper.evaluate_token(event).await {
+        Ok(true) => {
+            log::info!("Token passed filters. Executing buy...");
+
+            match sniper.execute_buy(event).await {
+                Ok(fill) => {
+                    log::info!(
+                        "Successfully sniped token {}: transaction {}",
+                        event.mint,
+                        fill.signature
+                    );
+
+                    if let Some(positions) = positions {
+                        positions.open(event, &fill);
                     }
-                } else {
-                    log::debug!("Token did not pass filters: {}", event.mint);
                 }
-            }
-            Err(e) => {
-                log::warn!("Error evaluating token {}: {}", event.mint, e);
+                Err(
+                    e @ (SnipeError::SlippageExceeded { .. }
+                    | SnipeError::CurveComplete
+                    | SnipeError::BuyLimitReached { .. }),
+                ) => {
+                    log::info!("Skipped buy for {}: {}", event.mint, e);
+                }
+                Err(e) => {
+                    log::error!("Failed to execute buy for {}: {}", event.mint, e);
+                }
             }
         }
-
-        // Rate limiting between snipes
-        utils::rate_limit_delay(config.rate_limit_ms).await;
+        Ok(false) => {
+            log::debug!("Token did not pass filters: {}", event.mint);
+        }
+        Err(e) => {
+            log::warn!("Error evaluating token {}: {}", event.mint, e);
+        }
     }
-
-    log::warn!("Event stream ended unexpectedly");
 }
@@ -0,0 +1,149 @@
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+/// PDA seeds used by the Pump.fun program
+pub mod seeds {
+    pub const GLOBAL: &[u8] = b"global";
+    pub const BONDING_CURVE: &[u8] = b"bonding-curve";
+    pub const EVENT_AUTHORITY: &[u8] = b"__event_authority";
+    pub const CREATOR_VAULT: &[u8] = b"creator-vault";
+    pub const GLOBAL_VOLUME_ACCUMULATOR: &[u8] = b"global_volume_accumulator";
+    pub const USER_VOLUME_ACCUMULATOR: &[u8] = b"user_volume_accumulator";
+    pub const FEE_CONFIG: &[u8] = b"fee_config";
+    pub const POOL_AUTHORITY: &[u8] = b"pool-authority";
+}
+
+/// PDA seeds used by the PumpSwap AMM program
+pub mod amm_seeds {
+    pub const POOL: &[u8] = b"pool";
+    pub const GLOBAL_CONFIG: &[u8] = b"global_config";
+    pub const COIN_CREATOR_VAULT: &[u8] = b"creator_vault";
+}
+
+/// Index of the pool Pump.fun creates when migrating a curve
+const CANONICAL_POOL_INDEX: u16 = 0;
+
+/// Global config account holding fee settings and initial curve reserves
+pub fn global(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::GLOBAL], program_id).0
+}
+
+/// Bonding curve account for a mint
+pub fn bonding_curve(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::BONDING_CURVE, mint.as_ref()], program_id).0
+}
+
+/// Token account holding the bonding curve's token reserves
+///
+/// This is the bonding curve's associated token account, so it depends on
+/// the mint's token program.
+pub fn associated_bonding_curve(bonding_curve: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(bonding_curve, mint, token_program)
+}
+
+/// Anchor event authority used for self-CPI events
+pub fn event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::EVENT_AUTHORITY], program_id).0
+}
+
+/// Vault collecting creator fees for a token creator
+pub fn creator_vault(program_id: &Pubkey, creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::CREATOR_VAULT, creator.as_ref()], program_id).0
+}
+
+/// Program-wide trading volume accumulator
+pub fn global_volume_accumulator(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::GLOBAL_VOLUME_ACCUMULATOR], program_id).0
+}
+
+/// Per-user trading volume accumulator
+pub fn user_volume_accumulator(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::USER_VOLUME_ACCUMULATOR, user.as_ref()], program_id).0
+}
+
+/// Fee tier config for the Pump.fun program, owned by the fee program
+pub fn fee_config(fee_program_id: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::FEE_CONFIG, program_id.as_ref()], fee_program_id).0
+}
+
+/// Pump.fun authority that creates and owns migrated PumpSwap pools
+pub fn pool_authority(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::POOL_AUTHORITY, mint.as_ref()], program_id).0
+}
+
+/// PumpSwap pool a completed curve migrates to
+pub fn canonical_pool(amm_program_id: &Pubkey, program_id: &Pubkey, mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            amm_seeds::POOL,
+            &CANONICAL_POOL_INDEX.to_le_bytes(),
+            pool_authority(program_id, mint).as_ref(),
+            mint.as_ref(),
+            quote_mint.as_ref(),
+        ],
+        amm_program_id,
+    )
+    .0
+}
+
+/// PumpSwap global config account holding fee settings
+pub fn amm_global_config(amm_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[amm_seeds::GLOBAL_CONFIG], amm_program_id).0
+}
+
+/// PumpSwap authority over a coin creator's fee vault
+pub fn coin_creator_vault_authority(amm_program_id: &Pubkey, coin_creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[amm_seeds::COIN_CREATOR_VAULT, coin_creator.as_ref()], amm_program_id).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PUMPFUN_PROGRAM_ID;
+    use std::str::FromStr;
+
+    fn key(address: &str) -> Pubkey {
+        Pubkey::from_str(address).unwrap()
+    }
+
+    fn program_id() -> Pubkey {
+        key(PUMPFUN_PROGRAM_ID)
+    }
+
+    #[test]
+    fn derives_program_wide_accounts() {
+        let program_id = program_id();
+
+        assert_eq!(global(&program_id), key("4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf"));
+        assert_eq!(event_authority(&program_id), key("Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1"));
+        assert_eq!(
+            global_volume_accumulator(&program_id),
+            key("Hq2wp8uJ9jCPsYgNHex8RtqdvMPfVGoYwjvF1ATiwn2Y")
+        );
+    }
+
+    #[test]
+    fn derives_curve_accounts_for_mint() {
+        let program_id = program_id();
+        let mint = key("9BB6NFEcjBCtnNLFko2FqVQBq8HHM13kCyYcdQbgpump");
+
+        let curve = bonding_curve(&program_id, &mint);
+        assert_eq!(curve, key("TBHe5tJnuT4CQbHorJ1uVdfUoaYGPKgfCpiv2jgesVN"));
+        assert_eq!(
+            associated_bonding_curve(&curve, &mint, &spl_token::ID),
+            key("4oPaRNdUyHNPn7oba9RnY66mkCUeUMZNVLmJ2X6J5a4Z")
+        );
+    }
+
+    #[test]
+    fn derives_accounts_for_creator_and_user() {
+        let program_id = program_id();
+        let wallet = key("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+
+        assert_eq!(creator_vault(&program_id, &wallet), key("36CNecFiDjcw4nydbdVrUYDFhwzWjqhBmmComjAtWAo5"));
+        assert_eq!(
+            user_volume_accumulator(&program_id, &wallet),
+            key("ASZB5x4iWFaadZo6DxwyhNVdAH4NfZryPhVeS3Ca9RE6")
+        );
+    }
+}
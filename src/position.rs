@@ -0,0 +1,533 @@
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio::time::{interval, sleep, Duration, Instant};
+use tokio_stream::StreamExt;
+
+use crate::bonding_curve::BondingCurve;
+use crate::config::Config;
+use crate::detector::{CurveCompletionEvent, CurveUpdate, TokenCreationEvent};
+use crate::error::PumpError;
+use crate::exit_strategy::{ExitDecision, ExitReason, ExitState, ExitStrategy};
+use crate::fill::BuyFill;
+use crate::sniper::{SellAmount, Sniper};
+use crate::utils;
+
+/// Initial delay before resubscribing to a bonding curve
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// How often hold time is checked when the curve is quiet
+const HOLD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Attempts at selling a position after its curve completes
+const COMPLETION_SELL_ATTEMPTS: u32 = 60;
+
+/// Delay between post-completion sell attempts, while migration catches up
+const COMPLETION_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often a post-completion sell checks whether an exit sell finished
+const SELL_WAIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Geyser curve updates of held positions buffered per position monitor
+const HELD_CURVE_UPDATES_CAPACITY: usize = 256;
+
+/// Further attempts at reading an estimated buy's fill
+const FILL_RETRY_ATTEMPTS: u32 = 12;
+
+/// Delay between attempts at reading an estimated buy's fill
+const FILL_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tokens held in one mint, from one or more buys
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub bonding_curve: Pubkey,
+    /// Raw token units held
+    pub tokens: u64,
+    /// Cost of the tokens still held in lamports, fees included
+    pub sol_spent: u64,
+    /// Lamports paid per raw token unit
+    pub entry_price: f64,
+    pub opened_at: Instant,
+    /// Progress through the exit strategy
+    pub exit: ExitState,
+    /// Curve completed; a post-completion sell has taken over
+    pub completing: bool,
+    /// An exit sell is in flight; no other sell of the mint starts meanwhile
+    pub selling: bool,
+    /// Buys counted from their quote until their fill is read; no
+    /// price-based exit is taken while any remain
+    pub estimated_fills: u32,
+}
+
+/// Tracks filled buys and sells them according to an [`ExitStrategy`]
+///
+/// Each position follows its bonding curve through `accountSubscribe`, and
+/// through the detector's Geyser curve updates when those are streamed;
+/// whichever reports a newer slot first is acted on.
+pub struct PositionManager {
+    sniper: Arc<Sniper>,
+    config: Config,
+    strategy: ExitStrategy,
+    positions: Mutex<HashMap<Pubkey, Position>>,
+    /// Geyser updates of held curves, passed on to the position monitors
+    held_curve_updates: broadcast::Sender<CurveUpdate>,
+}
+
+impl PositionManager {
+    pub fn new(sniper: Arc<Sniper>, config: Config, strategy: ExitStrategy) -> Arc<Self> {
+        Arc::new(Self {
+            sniper,
+            config,
+            strategy,
+            positions: Mutex::new(HashMap::new()),
+            held_curve_updates: broadcast::channel(HELD_CURVE_UPDATES_CAPACITY).0,
+        })
+    }
+
+    /// Record a filled buy and start watching its curve
+    ///
+    /// A further buy of a mint already held is added to that position.
+    pub fn open(self: &Arc<Self>, event: &TokenCreationEvent, fill: &BuyFill) {
+        if fill.tokens == 0 {
+            return;
+        }
+
+        if fill.estimated {
+            tokio::spawn(self.clone().reconcile_fill(event.mint, fill.clone()));
+        }
+
+        let mut positions = self.positions.lock().unwrap();
+        if let Some(position) = positions.get_mut(&event.mint) {
+            position.tokens += fill.tokens;
+            position.sol_spent += fill.sol_spent;
+            position.entry_price = position.sol_spent as f64 / position.tokens as f64;
+            position.estimated_fills += fill.estimated as u32;
+            log::info!(
+                "Added {} tokens to position in {} ({} held)",
+                fill.tokens,
+                event.mint,
+                position.tokens
+            );
+            return;
+        }
+
+        let position = Position {
+            bonding_curve: event.bonding_curve,
+            tokens: fill.tokens,
+            sol_spent: fill.sol_spent,
+            entry_price: fill.price,
+            opened_at: Instant::now(),
+            exit: ExitState::default(),
+            completing: false,
+            selling: false,
+            estimated_fills: fill.estimated as u32,
+        };
+        log::info!(
+            "Opened position in {}: {} tokens for {:.6} SOL ({:.4} lamports/token){}",
+            event.mint,
+            position.tokens,
+            utils::lamports_to_sol(position.sol_spent),
+            position.entry_price,
+            if fill.estimated { ", estimated from the quote" } else { "" }
+        );
+        positions.insert(event.mint, position);
+
+        tokio::spawn(self.clone().monitor(event.mint, event.bonding_curve));
+    }
+
+    /// Replace an estimated fill with the one read from its transaction
+    ///
+    /// Until then the position's tokens and cost are the quote's, so only
+    /// hold-time and curve-progress exits apply. Those close the whole
+    /// position, so the estimate is still in it when the real fill arrives.
+    async fn reconcile_fill(self: Arc<Self>, mint: Pubkey, estimate: BuyFill) {
+        for attempt in 1..=FILL_RETRY_ATTEMPTS {
+            sleep(FILL_RETRY_INTERVAL).await;
+
+            let fill = match self.sniper.fetch_buy_fill(&estimate.signature, &mint).await {
+                Ok(fill) => fill,
+                Err(e) => {
+                    log::debug!(
+                        "Fill of {} still unreadable (attempt {}/{}): {:#}",
+                        estimate.signature,
+                        attempt,
+                        FILL_RETRY_ATTEMPTS,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let mut positions = self.positions.lock().unwrap();
+            let Some(position) = positions.get_mut(&mint) else {
+                return;
+            };
+            position.tokens = position.tokens.saturating_sub(estimate.tokens) + fill.tokens;
+            position.sol_spent = position.sol_spent.saturating_sub(estimate.sol_spent) + fill.sol_spent;
+            position.entry_price = position.sol_spent as f64 / position.tokens.max(1) as f64;
+            position.estimated_fills -= 1;
+            log::info!(
+                "Read fill of {}: {} tokens for {:.6} SOL (estimated {} tokens for {:.6} SOL)",
+                estimate.signature,
+                fill.tokens,
+                utils::lamports_to_sol(fill.sol_spent),
+                estimate.tokens,
+                utils::lamports_to_sol(estimate.sol_spent)
+            );
+            return;
+        }
+
+        log::error!(
+            "Could not read fill of {}; position in {} keeps only hold-time and curve-progress exits",
+            estimate.signature,
+            mint
+        );
+    }
+
+    /// Sell held tokens whose curve the detector reports completed or migrated
+    pub fn watch_completions(self: &Arc<Self>, mut completions: broadcast::Receiver<CurveCompletionEvent>) {
+        let manager = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match completions.recv().await {
+                    Ok(event) => {
+                        if !manager.positions.lock().unwrap().contains_key(&event.mint) {
+                            continue;
+                        }
+
+                        log::info!(
+                            "Bonding curve of held token {}: {:?} in {}",
+                            event.mint,
+                            event.stage,
+                            event.signature
+                        );
+                        let manager = manager.clone();
+                        tokio::spawn(async move {
+                            manager.close_after_completion(&event.mint).await;
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Missed {} curve completion events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+
+    /// Feed Geyser bonding curve updates of held tokens to their monitors
+    pub fn watch_curve_updates(self: &Arc<Self>, mut updates: broadcast::Receiver<CurveUpdate>) {
+        let manager = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match updates.recv().await {
+                    Ok(update) => {
+                        let held = manager
+                            .positions
+                            .lock()
+                            .unwrap()
+                            .values()
+                            .any(|position| position.bonding_curve == update.bonding_curve);
+                        if held {
+                            // No monitor listening is fine
+                            let _ = manager.held_curve_updates.send(update);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Missed {} bonding curve updates", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+
+    /// Follow a position's bonding curve until the position is closed
+    async fn monitor(self: Arc<Self>, mint: Pubkey, bonding_curve: Pubkey) {
+        let ws_url = self.config.websocket_url();
+        let max_reconnect_delay = Duration::from_millis(self.config.geyser_max_reconnect_delay_ms);
+        let account_config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        let mut hold_check = interval(HOLD_CHECK_INTERVAL);
+        let mut geyser_updates = self.held_curve_updates.subscribe();
+        // Latest slot acted on, so a source lagging the other is ignored
+        let mut last_slot = 0;
+
+        loop {
+            let client = match PubsubClient::new(&ws_url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    log::warn!("Position monitor for {} could not connect: {}", mint, e);
+                    if self.check_exit(&mint, None).await {
+                        return;
+                    }
+                    sleep(delay).await;
+                    delay = (delay * 2).min(max_reconnect_delay);
+                    continue;
+                }
+            };
+
+            let (mut updates, _unsubscribe) = match client
+                .account_subscribe(&bonding_curve, Some(account_config.clone()))
+                .await
+            {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    log::warn!("accountSubscribe for {} failed: {}", bonding_curve, e);
+                    sleep(delay).await;
+                    delay = (delay * 2).min(max_reconnect_delay);
+                    continue;
+                }
+            };
+            delay = INITIAL_RECONNECT_DELAY;
+
+            loop {
+                tokio::select! {
+                    update = updates.next() => {
+                        let Some(update) = update else { break };
+                        if update.context.slot < last_slot {
+                            continue;
+                        }
+                        let Some(data) = update.value.data.decode() else { continue };
+
+                        match BondingCurve::decode(&data) {
+                            Ok(curve) => {
+                                last_slot = update.context.slot;
+                                if self.check_exit(&mint, Some(&curve)).await {
+                                    return;
+                                }
+                            }
+                            Err(e) => log::debug!("Undecodable curve update for {}: {}", mint, e),
+                        }
+                    }
+                    update = geyser_updates.recv() => {
+                        let update = match update {
+                            Ok(update) => update,
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            // The manager owns the sender and outlives this task
+                            Err(broadcast::error::RecvError::Closed) => return,
+                        };
+                        if update.bonding_curve != bonding_curve || update.slot < last_slot {
+                            continue;
+                        }
+
+                        last_slot = update.slot;
+                        if self.check_exit(&mint, Some(&update.curve)).await {
+                            return;
+                        }
+                    }
+                    _ = hold_check.tick() => {
+                        if self.check_exit(&mint, None).await {
+                            return;
+                        }
+                    }
+                }
+            }
+
+            log::warn!("Bonding curve subscription for {} ended; resubscribing", mint);
+        }
+    }
+
+    /// Sell whatever the strategy calls for; returns true once the
+    /// position is closed
+    async fn check_exit(&self, mint: &Pubkey, curve: Option<&BondingCurve>) -> bool {
+        let Some(position) = self.positions.lock().unwrap().get(mint).cloned() else {
+            return true;
+        };
+        if position.completing {
+            return true;
+        }
+
+        let (value, progress) = match curve {
+            Some(curve) if curve.complete => return self.close_after_completion(mint).await,
+            Some(curve) => match self.sniper.global().await {
+                Ok(global) => {
+                    // Without the tiers the flat Global rates still give a
+                    // close enough value
+                    let fee_config = match self.sniper.fee_config().await {
+                        Ok(fee_config) => Some(fee_config),
+                        Err(e) => {
+                            log::warn!("Failed to fetch fee config: {}", e);
+                            None
+                        }
+                    };
+                    (
+                        Some(curve.quote_sell(global, fee_config, position.tokens).net_sol),
+                        Some(curve.progress(global)),
+                    )
+                }
+                Err(e) => {
+                    log::warn!("Failed to fetch global config: {}", e);
+                    (None, None)
+                }
+            },
+            None => (None, None),
+        };
+
+        // An estimated cost would make the multiple up as well
+        let multiple = value
+            .filter(|_| position.estimated_fills == 0)
+            .map(|value| value as f64 / position.sol_spent.max(1) as f64);
+
+        let mut exit = position.exit.clone();
+        if let (Some(multiple), Some(curve)) = (multiple, curve) {
+            exit.observe(multiple);
+            if let Some(tracked) = self.positions.lock().unwrap().get_mut(mint) {
+                tracked.exit.observe(multiple);
+            }
+
+            log::debug!(
+                "Position {}: {:.2}x (peak {:.2}x), price {:.4} vs entry {:.4} lamports/token",
+                mint,
+                multiple,
+                exit.peak_multiple,
+                curve.price(),
+                position.entry_price
+            );
+        }
+
+        // Leave before completion when configured, while the curve still trades
+        let near_completion = match (progress, self.config.sell_at_curve_progress) {
+            (Some(progress), Some(threshold)) => progress >= threshold,
+            _ => false,
+        };
+
+        let decision = if near_completion {
+            Some(ExitDecision {
+                reason: ExitReason::CurveProgress,
+                sell_fraction: 1.0,
+                steps_filled: exit.steps_filled,
+            })
+        } else {
+            self.strategy.next_exit(&exit, multiple, position.opened_at.elapsed())
+        };
+        let Some(decision) = decision else {
+            return false;
+        };
+
+        // Sell from the live position, claiming it so no other sell of the
+        // mint runs until this one settles
+        let tokens = {
+            let mut positions = self.positions.lock().unwrap();
+            let Some(tracked) = positions.get_mut(mint) else {
+                return true;
+            };
+            if tracked.completing {
+                return true;
+            }
+            if tracked.selling {
+                return false;
+            }
+            tracked.selling = true;
+            if decision.closes() {
+                tracked.tokens
+            } else {
+                (tracked.tokens as f64 * decision.sell_fraction) as u64
+            }
+        };
+
+        log::info!("Selling {} tokens of {} ({:?})", tokens, mint, decision.reason);
+
+        let result = self.sniper.execute_sell(mint, SellAmount::Units(tokens)).await;
+
+        let error = {
+            let mut positions = self.positions.lock().unwrap();
+            let Some(tracked) = positions.get_mut(mint) else {
+                return true;
+            };
+            tracked.selling = false;
+
+            match result {
+                Err(e) => e,
+                // Buys added while the sell was in flight stay in the position
+                Ok(_) if tokens >= tracked.tokens => {
+                    positions.remove(mint);
+                    log::info!("Closed position in {}", mint);
+                    return true;
+                }
+                Ok(_) => {
+                    let cost = tracked.sol_spent as u128 * tokens as u128 / tracked.tokens as u128;
+                    tracked.sol_spent = tracked.sol_spent.saturating_sub(cost as u64);
+                    tracked.tokens -= tokens;
+                    tracked.exit.apply(&decision);
+                    log::info!("{} tokens of {} still held", tracked.tokens, mint);
+                    return false;
+                }
+            }
+        };
+
+        // The curve completed under the sell; the position moves to PumpSwap
+        if error.pump_error() == Some(PumpError::BondingCurveComplete) {
+            return self.close_after_completion(mint).await;
+        }
+        log::error!("Failed to sell from position in {}: {:#}", mint, error);
+        false
+    }
+
+    /// Sell a position whose curve completed, on PumpSwap once migrated
+    ///
+    /// Waits for an exit sell already in flight to settle, then sells what
+    /// the position holds at each attempt. Migration can trail completion,
+    /// so the sell is retried until the pool exists. Returns true once the
+    /// position is no longer tracked.
+    async fn close_after_completion(&self, mint: &Pubkey) -> bool {
+        {
+            let mut positions = self.positions.lock().unwrap();
+            match positions.get_mut(mint) {
+                Some(position) if !position.completing => position.completing = true,
+                // Already closed or being closed by another task
+                _ => return true,
+            }
+        }
+
+        log::info!("Bonding curve for {} completed; selling after migration", mint);
+
+        for attempt in 1..=COMPLETION_SELL_ATTEMPTS {
+            let tokens = loop {
+                match self.positions.lock().unwrap().get(mint) {
+                    None => return true,
+                    Some(position) if !position.selling => break position.tokens,
+                    Some(_) => {}
+                }
+                sleep(SELL_WAIT_INTERVAL).await;
+            };
+
+            match self.sniper.execute_sell(mint, SellAmount::Units(tokens)).await {
+                Ok(signature) => {
+                    log::info!(
+                        "Closed position in {} after completion: sold {} tokens in transaction {}",
+                        mint,
+                        tokens,
+                        signature
+                    );
+                    self.positions.lock().unwrap().remove(mint);
+                    return true;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Post-completion sell of {} failed (attempt {}/{}): {:#}",
+                        mint,
+                        attempt,
+                        COMPLETION_SELL_ATTEMPTS,
+                        e
+                    );
+                    sleep(COMPLETION_RETRY_INTERVAL).await;
+                }
+            }
+        }
+
+        log::error!("Giving up on selling {}; use the `sell` command once it trades on PumpSwap", mint);
+        self.positions.lock().unwrap().remove(mint);
+        true
+    }
+}
@@ -0,0 +1,131 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration};
+
+use crate::config::{Config, PriorityFeeStrategy, PUMPFUN_PROGRAM_ID};
+
+/// Slots of fee history kept in the rolling window
+const WINDOW_SLOTS: usize = 300;
+
+/// Recently traded bonding curves included in fee sampling
+const TRACKED_ACCOUNTS: usize = 4;
+
+/// Priority fee estimator backed by `getRecentPrioritizationFees`
+///
+/// A background task samples fees for transactions touching the Pump.fun
+/// program and recently traded bonding curves, keeping a per-slot rolling
+/// window that estimates are computed from without any RPC on the hot path.
+pub struct PriorityFeeEstimator {
+    strategy: PriorityFeeStrategy,
+    base_fee: u64,
+    percentile: u8,
+    multiplier: f64,
+    cap: u64,
+    refresh_interval: Duration,
+    program_id: Pubkey,
+    /// Fee per slot, oldest slot first
+    window: Mutex<BTreeMap<u64, u64>>,
+    tracked_accounts: Mutex<VecDeque<Pubkey>>,
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            strategy: config.priority_fee_strategy,
+            base_fee: config.priority_fee_micro_lamports,
+            percentile: config.priority_fee_percentile.min(100),
+            multiplier: config.priority_fee_multiplier,
+            cap: config.priority_fee_cap_micro_lamports,
+            refresh_interval: Duration::from_millis(config.priority_fee_refresh_ms),
+            program_id: Pubkey::from_str(PUMPFUN_PROGRAM_ID).unwrap_or_default(),
+            window: Mutex::new(BTreeMap::new()),
+            tracked_accounts: Mutex::new(VecDeque::with_capacity(TRACKED_ACCOUNTS)),
+        }
+    }
+
+    /// Start background sampling (no-op for the fixed strategy)
+    pub fn start(self: &Arc<Self>, rpc_url: String) {
+        if self.strategy == PriorityFeeStrategy::Fixed {
+            return;
+        }
+
+        let estimator = Arc::clone(self);
+        tokio::spawn(async move {
+            let client = RpcClient::new(rpc_url);
+            loop {
+                estimator.refresh(&client).await;
+                sleep(estimator.refresh_interval).await;
+            }
+        });
+    }
+
+    /// Include an account (e.g. a bonding curve about to be traded) in sampling
+    pub fn track_account(&self, account: Pubkey) {
+        let mut tracked = self.tracked_accounts.lock().unwrap();
+        if tracked.contains(&account) {
+            return;
+        }
+        if tracked.len() == TRACKED_ACCOUNTS {
+            tracked.pop_front();
+        }
+        tracked.push_back(account);
+    }
+
+    /// Sample recent fees and merge them into the window
+    async fn refresh(&self, client: &RpcClient) {
+        let mut accounts = vec![self.program_id];
+        accounts.extend(self.tracked_accounts.lock().unwrap().iter().copied());
+
+        match client.get_recent_prioritization_fees(&accounts).await {
+            Ok(fees) => {
+                let mut window = self.window.lock().unwrap();
+                for fee in fees {
+                    window.insert(fee.slot, fee.prioritization_fee);
+                }
+                while window.len() > WINDOW_SLOTS {
+                    window.pop_first();
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to sample recent prioritization fees: {}", e);
+            }
+        }
+    }
+
+    /// Current priority fee in micro-lamports per compute unit
+    ///
+    /// Falls back to the configured base fee until samples are available.
+    pub fn estimate(&self) -> u64 {
+        if self.strategy == PriorityFeeStrategy::Fixed {
+            return self.base_fee;
+        }
+
+        let sampled = match self.window_percentile() {
+            Some(fee) => fee,
+            None => return self.base_fee,
+        };
+
+        let fee = if self.strategy == PriorityFeeStrategy::PercentileMultiplier {
+            (sampled as f64 * self.multiplier) as u64
+        } else {
+            sampled
+        };
+
+        fee.min(self.cap)
+    }
+
+    /// Configured percentile of the fees in the window
+    fn window_percentile(&self) -> Option<u64> {
+        let mut fees: Vec<u64> = self.window.lock().unwrap().values().copied().collect();
+        if fees.is_empty() {
+            return None;
+        }
+
+        fees.sort_unstable();
+        let rank = (self.percentile as usize * fees.len() + 99) / 100;
+        Some(fees[rank.saturating_sub(1)])
+    }
+}
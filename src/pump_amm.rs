@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::{AMM_GLOBAL_CONFIG_DISCRIMINATOR, POOL_DISCRIMINATOR};
+use crate::utils::{ceil_bps, BorshReader};
+
+/// Number of protocol fee recipients in the global config
+const PROTOCOL_FEE_RECIPIENTS: usize = 8;
+
+/// PumpSwap `Pool` account
+#[derive(Debug, Clone)]
+pub struct Pool {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    /// Default pubkey on pools created before coin creator fees existed
+    pub coin_creator: Pubkey,
+}
+
+/// PumpSwap `GlobalConfig` account
+#[derive(Debug, Clone)]
+pub struct GlobalConfig {
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub protocol_fee_recipients: Vec<Pubkey>,
+    pub coin_creator_fee_basis_points: u64,
+}
+
+/// Proceeds breakdown of a pool sell, in quote (SOL) lamports
+#[derive(Debug, Clone, Copy)]
+pub struct AmmSellQuote {
+    /// Quote released by the pool, before fees
+    pub quote_out: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub coin_creator_fee: u64,
+    /// What the seller receives
+    pub net_quote: u64,
+}
+
+impl Pool {
+    /// Deserialize the account data, including its discriminator
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&POOL_DISCRIMINATOR)
+            .context("Account is not a PumpSwap pool")?;
+        let mut reader = BorshReader::new(payload);
+
+        reader.read_u8().context("Pool: pool_bump")?;
+        reader.read_bytes(2).context("Pool: index")?;
+        reader.read_pubkey().context("Pool: creator")?;
+        let base_mint = reader.read_pubkey().context("Pool: base_mint")?;
+        let quote_mint = reader.read_pubkey().context("Pool: quote_mint")?;
+        reader.read_pubkey().context("Pool: lp_mint")?;
+        let pool_base_token_account = reader.read_pubkey().context("Pool: pool_base_token_account")?;
+        let pool_quote_token_account = reader.read_pubkey().context("Pool: pool_quote_token_account")?;
+        reader.read_u64().context("Pool: lp_supply")?;
+
+        Ok(Self {
+            base_mint,
+            quote_mint,
+            pool_base_token_account,
+            pool_quote_token_account,
+            coin_creator: reader.read_pubkey().unwrap_or_default(),
+        })
+    }
+
+    /// Fetch and decode a pool account
+    pub async fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = rpc_client
+            .get_account_data(address)
+            .await
+            .with_context(|| format!("Failed to fetch PumpSwap pool {}", address))?;
+
+        Self::decode(&data)
+    }
+
+    /// Base and quote reserves held by the pool's token accounts
+    pub async fn fetch_reserves(&self, rpc_client: &RpcClient) -> Result<(u64, u64)> {
+        let base = rpc_client
+            .get_token_account_balance(&self.pool_base_token_account)
+            .await
+            .context("Failed to fetch pool base reserves")?;
+        let quote = rpc_client
+            .get_token_account_balance(&self.pool_quote_token_account)
+            .await
+            .context("Failed to fetch pool quote reserves")?;
+
+        Ok((
+            base.amount.parse().context("Invalid pool base reserves")?,
+            quote.amount.parse().context("Invalid pool quote reserves")?,
+        ))
+    }
+
+    /// Quote for selling `base_in` tokens into the pool, after fees
+    pub fn quote_sell(&self, config: &GlobalConfig, base_reserve: u64, quote_reserve: u64, base_in: u64) -> AmmSellQuote {
+        let quote_out = (base_in as u128 * quote_reserve as u128
+            / (base_reserve as u128 + base_in as u128)) as u64;
+
+        let lp_fee = ceil_bps(quote_out, config.lp_fee_basis_points);
+        let protocol_fee = ceil_bps(quote_out, config.protocol_fee_basis_points);
+        let coin_creator_fee = if self.coin_creator == Pubkey::default() {
+            0
+        } else {
+            ceil_bps(quote_out, config.coin_creator_fee_basis_points)
+        };
+
+        AmmSellQuote {
+            quote_out,
+            lp_fee,
+            protocol_fee,
+            coin_creator_fee,
+            net_quote: quote_out.saturating_sub(lp_fee + protocol_fee + coin_creator_fee),
+        }
+    }
+}
+
+impl GlobalConfig {
+    /// Deserialize the account data, including its discriminator
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let payload = data
+            .strip_prefix(&AMM_GLOBAL_CONFIG_DISCRIMINATOR)
+            .context("Account is not the PumpSwap GlobalConfig")?;
+        let mut reader = BorshReader::new(payload);
+
+        reader.read_pubkey().context("GlobalConfig: admin")?;
+        let lp_fee_basis_points = reader.read_u64().context("GlobalConfig: lp_fee_basis_points")?;
+        let protocol_fee_basis_points = reader.read_u64().context("GlobalConfig: protocol_fee_basis_points")?;
+        reader.read_u8().context("GlobalConfig: disable_flags")?;
+        let protocol_fee_recipients = (0..PROTOCOL_FEE_RECIPIENTS)
+            .map(|_| reader.read_pubkey())
+            .collect::<Option<Vec<_>>>()
+            .context("GlobalConfig: protocol_fee_recipients")?;
+
+        Ok(Self {
+            lp_fee_basis_points,
+            protocol_fee_basis_points,
+            protocol_fee_recipients,
+            coin_creator_fee_basis_points: reader.read_u64().unwrap_or(0),
+        })
+    }
+
+    /// Fetch and decode the GlobalConfig account
+    pub async fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = rpc_client
+            .get_account_data(address)
+            .await
+            .context("Failed to fetch PumpSwap GlobalConfig account")?;
+
+        Self::decode(&data)
+    }
+
+    /// A configured protocol fee recipient
+    pub fn protocol_fee_recipient(&self) -> Option<Pubkey> {
+        self.protocol_fee_recipients
+            .iter()
+            .copied()
+            .find(|recipient| *recipient != Pubkey::default())
+    }
+}
@@ -1,41 +1,113 @@
 use anyhow::{Context, Result};
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
     pubkey::Pubkey,
-    signature::Signer,
-    system_program,
-    transaction::VersionedTransaction,
+    signature::{Signature, Signer},
 };
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
 use tokio::time::{sleep, Duration};
 
-use crate::config::Config;
+use crate::blockhash::{BlockhashCache, RecentBlockhash};
+use crate::bonding_curve::{BondingCurve, FeeConfig, Global};
+use crate::broadcast::Broadcaster;
+use crate::config::{Config, PUMPFUN_FEE_PROGRAM_ID, PUMPFUN_PROGRAM_ID, PUMP_AMM_PROGRAM_ID};
+use crate::confirm::{Confirmer, TxOutcome};
 use crate::detector::TokenCreationEvent;
-use crate::instructions::build_buy_instruction;
+use crate::error::SnipeError;
+use crate::fill::{self, BuyFill};
+use crate::instructions::{build_amm_sell_instruction, build_buy_instruction, build_sell_instruction};
+use crate::ledger::BuyLedger;
+use crate::pda;
+use crate::priority_fee::PriorityFeeEstimator;
+use crate::pump_amm;
+use crate::tx_builder::TransactionBuilder;
 use crate::utils;
 use crate::wallet::Wallet;
 // For now, using a placeholder
 
+/// Transactions signed and sent per buy or sell before giving up
+const MAX_SEND_ATTEMPTS: u32 = 3;
+
+/// Buy parameters derived from a bonding curve quote
+struct BuyPlan {
+    token_amount: u64,
+    /// Cost of `token_amount` on the curve as the buy was built
+    quoted_sol: u64,
+    max_sol_cost: u64,
+    creator: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+/// How much of a holding to sell
+#[derive(Debug, Clone, Copy)]
+pub enum SellAmount {
+    /// Number of raw token units
+    Units(u64),
+    /// Number of tokens, in UI units
+    Tokens(f64),
+    /// Percentage of the wallet's balance
+    Percent(f64),
+}
+
 /// Sniper that evaluates and executes buys on new tokens
 pub struct Sniper {
     rpc_client: RpcClient,
     wallet: Wallet,
     config: Config,
+    global: OnceCell<Global>,
+    fee_config: OnceCell<FeeConfig>,
+    priority_fees: Arc<PriorityFeeEstimator>,
+    blockhashes: Arc<BlockhashCache>,
+    /// Sends transactions to every RPC endpoint and Jito
+    broadcaster: Broadcaster,
+    confirmer: Confirmer,
+    /// Buys made per mint and creator; checked before every buy is signed
+    ledger: BuyLedger,
+    /// Curve state seen when each token passed evaluation, keyed by mint
+    evaluated_curves: Mutex<HashMap<Pubkey, BondingCurve>>,
 }
 
 impl Sniper {
-    pub fn new(rpc_url: String, wallet: Wallet, config: Config) -> Self {
+    /// Create a sniper; also starts background blockhash refreshing, and
+    /// priority fee sampling when a sampled fee strategy is configured
+    pub fn new(rpc_url: String, wallet: Wallet, config: Config, ledger: BuyLedger) -> Self {
+        let priority_fees = Arc::new(PriorityFeeEstimator::new(&config));
+        priority_fees.start(rpc_url.clone());
+
+        let blockhashes = Arc::new(BlockhashCache::new(&config));
+        blockhashes.start(rpc_url.clone());
+
         let rpc_client = RpcClient::new_with_commitment(
             rpc_url,
             CommitmentConfig::confirmed(),
         );
 
+        let broadcaster = Broadcaster::new(&config);
+        let confirmer = Confirmer::new(&config);
+
         Self {
             rpc_client,
             wallet,
             config,
+            global: OnceCell::new(),
+            fee_config: OnceCell::new(),
+            priority_fees,
+            blockhashes,
+            broadcaster,
+            confirmer,
+            ledger,
+            evaluated_curves: Mutex::new(HashMap::new()),
         }
     }
 
@@ -54,175 +126,589 @@ impl Sniper {
             return Ok(false);
         }
 
-        // Check initial liquidity (if available)
-        // Note: This would require fetching bonding curve account data
-        // For now, we'll skip this check or make it optional
-        if let Err(e) = self.check_liquidity(&event.bonding_curve).await {
-            log::warn!("Failed to check liquidity: {}", e);
-            // Continue anyway - liquidity check is optional
+        // Check initial liquidity from the bonding curve account
+        let curve = match self.check_liquidity(&event.bonding_curve).await {
+            Ok(Some(curve)) => Some(curve),
+            Ok(None) => return Ok(false),
+            Err(e) => {
+                log::warn!("Failed to check liquidity: {}", e);
+                // Continue anyway - liquidity check is optional
+                None
+            }
+        };
+
+        // Check metadata (name/symbol) if it was decoded
+        if !event.name.is_empty() && utils::is_spam_name(&event.name) {
+            log::info!("Token name looks like spam: {}", event.name);
+            return Ok(false);
+        }
+
+        if !event.symbol.is_empty() && utils::is_spam_symbol(&event.symbol) {
+            log::info!("Token symbol looks like spam: {}", event.symbol);
+            return Ok(false);
         }
 
-        // Check metadata (name/symbol) if available
-        // This would require fetching token metadata
-        // For now, we'll proceed
+        // Sample fees on this curve from now on, it is about to be contended
+        self.priority_fees.track_account(event.bonding_curve);
+
+        // Remember the curve as evaluated so the buy can tell how far it moved
+        if let Some(curve) = curve {
+            self.evaluated_curves.lock().unwrap().insert(event.mint, curve);
+        }
 
         log::info!("Token passed all filters: {}", event.mint);
         Ok(true)
     }
 
     /// Check if token meets liquidity requirements
-    async fn check_liquidity(&self, _bonding_curve: &Pubkey) -> Result<()> {
-        // Fetch bonding curve account data
-        // Parse to get initial liquidity
-        // Compare against min/max thresholds
-        
-        // Placeholder - implement based on bonding curve account structure
-        // You'll need to:
-        // 1. Fetch account data
-        // 2. Deserialize bonding curve account
-        // 3. Extract virtual/real SOL reserves
-        // 4. Check against config thresholds
-        
-        Ok(())
+    ///
+    /// Liquidity is the real SOL held by the curve, i.e. what the creator and
+    /// any earlier buyers have paid in. Completed curves never qualify.
+    /// Returns the curve when it passes.
+    async fn check_liquidity(&self, bonding_curve: &Pubkey) -> Result<Option<BondingCurve>> {
+        let curve = BondingCurve::fetch(&self.rpc_client, bonding_curve).await?;
+
+        if curve.complete {
+            log::info!("Bonding curve already complete: {}", bonding_curve);
+            return Ok(None);
+        }
+
+        let liquidity_sol = utils::lamports_to_sol(curve.real_sol_reserves);
+
+        if liquidity_sol < self.config.min_initial_liquidity_sol {
+            log::info!(
+                "Liquidity {:.4} SOL below minimum {:.4} SOL",
+                liquidity_sol,
+                self.config.min_initial_liquidity_sol
+            );
+            return Ok(None);
+        }
+
+        if let Some(max_liquidity) = self.config.max_initial_liquidity_sol {
+            if liquidity_sol > max_liquidity {
+                log::info!(
+                    "Liquidity {:.4} SOL above maximum {:.4} SOL",
+                    liquidity_sol,
+                    max_liquidity
+                );
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(curve))
     }
 
-    /// Execute a buy on a token
-    pub async fn execute_buy(&self, event: &TokenCreationEvent) -> Result<String> {
+    /// Quote a buy and bound its cost by the configured slippage
+    ///
+    /// The target token amount comes from the curve as it was when the token
+    /// was evaluated; `max_sol_cost` allows `slippage_bps` on top of that
+    /// quote. If the current curve already prices the target above that
+    /// bound, the buy is refused rather than sent to fail on-chain. A curve
+    /// the RPC cannot return yet is quoted from its initial state; the
+    /// program still enforces `max_sol_cost` if it has moved since.
+    async fn quote_buy(
+        &self,
+        event: &TokenCreationEvent,
+        evaluated_curve: Option<BondingCurve>,
+        buy_amount_lamports: u64,
+    ) -> Result<BuyPlan, SnipeError> {
+        let global = self.global().await?;
+        let fee_config = self.fee_config().await?;
+        let current_curve = match BondingCurve::fetch(&self.rpc_client, &event.bonding_curve).await {
+            Ok(curve) => curve,
+            Err(e) => {
+                log::warn!(
+                    "Bonding curve {} not readable yet, quoting from its initial state: {:#}",
+                    event.bonding_curve,
+                    e
+                );
+                BondingCurve::initial(global, event.creator)
+            }
+        };
+
+        if current_curve.complete {
+            return Err(SnipeError::CurveComplete);
+        }
+
+        let reference_curve = evaluated_curve.as_ref().unwrap_or(&current_curve);
+        let target = reference_curve.quote_buy_by_sol(global, Some(fee_config), buy_amount_lamports);
+        if target.tokens == 0 {
+            return Err(anyhow::anyhow!("Buy amount of {} lamports buys no tokens", buy_amount_lamports).into());
+        }
+
+        let max_sol_cost = utils::add_slippage(target.total_sol, self.config.slippage_bps);
+        let current = current_curve.quote_buy_by_tokens(global, Some(fee_config), target.tokens);
+
+        if current.total_sol > max_sol_cost {
+            return Err(SnipeError::SlippageExceeded {
+                tokens: target.tokens,
+                cost: current.total_sol,
+                limit: max_sol_cost,
+            });
+        }
+
+        log::info!(
+            "Buying {} tokens: quote {:.6} SOL, current {:.6} SOL, max {:.6} SOL",
+            target.tokens,
+            utils::lamports_to_sol(target.total_sol),
+            utils::lamports_to_sol(current.total_sol),
+            utils::lamports_to_sol(max_sol_cost)
+        );
+
+        // Creator fees go to the curve's creator; older curves predate it
+        let creator = if current_curve.creator == Pubkey::default() {
+            event.creator
+        } else {
+            current_curve.creator
+        };
+
+        Ok(BuyPlan {
+            token_amount: target.tokens,
+            quoted_sol: current.total_sol,
+            max_sol_cost,
+            creator,
+            fee_recipient: global.fee_recipient,
+        })
+    }
+
+    /// Pump.fun Global account, fetched once and cached
+    pub async fn global(&self) -> Result<&Global> {
+        self.global
+            .get_or_try_init(|| async {
+                let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
+                    .context("Failed to parse Pump.fun program ID")?;
+                Global::fetch(&self.rpc_client, &pda::global(&program_id)).await
+            })
+            .await
+    }
+
+    /// Pump.fun fee tiers, fetched once and cached
+    pub async fn fee_config(&self) -> Result<&FeeConfig> {
+        self.fee_config
+            .get_or_try_init(|| async {
+                let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
+                    .context("Failed to parse Pump.fun program ID")?;
+                let fee_program_id = Pubkey::from_str(PUMPFUN_FEE_PROGRAM_ID)
+                    .context("Failed to parse Pump.fun fee program ID")?;
+                FeeConfig::fetch(&self.rpc_client, &pda::fee_config(&fee_program_id, &program_id)).await
+            })
+            .await
+    }
+
+    /// Execute a buy on a token and return what it filled at
+    ///
+    /// Refused when the buy ledger already holds the allowed number of buys
+    /// of the mint or its creator. The fill is read from the confirmed
+    /// transaction. If that cannot be fetched, the quote the buy was built
+    /// from stands in for it, marked as [`BuyFill::estimated`].
+    pub async fn execute_buy(&self, event: &TokenCreationEvent) -> Result<BuyFill, SnipeError> {
+        // Taken whatever the outcome, so no curve outlives its buy attempt
+        let evaluated_curve = self.evaluated_curves.lock().unwrap().remove(&event.mint);
+
         if self.config.dry_run {
             log::info!(
                 "[DRY RUN] Would buy token: mint={}, amount={} SOL",
                 event.mint,
                 self.config.buy_amount_sol
             );
-            return Ok("dry_run_simulation".to_string());
+            return Ok(BuyFill::new("dry_run_simulation".to_string(), 0, 0, 0, 0));
         }
 
+        // Reserve the buy before anything is signed; a buy known not to
+        // have landed gives its reservation back
+        self.ledger.reserve(&event.mint, &event.creator).await?;
+
+        let result = self.buy(event, evaluated_curve).await;
+        if let Err(e) = &result {
+            if e.may_have_landed() {
+                log::warn!("Keeping the ledger reservation for {}: {}", event.mint, e);
+            } else {
+                self.ledger.release(&event.mint, &event.creator).await;
+            }
+        }
+        result
+    }
+
+    /// Quote, sign and send a buy, then read its fill
+    async fn buy(&self, event: &TokenCreationEvent, evaluated_curve: Option<BondingCurve>) -> Result<BuyFill, SnipeError> {
         log::info!(
             "Executing buy: mint={}, amount={} SOL",
             event.mint,
             self.config.buy_amount_sol
         );
 
-        // Get latest blockhash
-        let (blockhash, _) = self
-            .rpc_client
-            .get_latest_blockhash()
-            .await
-            .context("Failed to get latest blockhash")?;
-
-        // Build buy instruction
-        // Note: This is simplified - you'll need to:
-        // 1. Derive associated token account
-        // 2. Get SOL reserves account
-        // 3. Calculate min tokens out with slippage
-        // 4. Build the actual instruction
-        
         let buy_amount_lamports = utils::sol_to_lamports(self.config.buy_amount_sol);
         
-        // Derive associated bonding curve
-        let associated_bonding_curve = self.derive_associated_bonding_curve(&event.bonding_curve)?;
-        
-        // Get associated token account for buyer
-        let buyer_token_account = self.get_or_create_token_account(&event.mint).await?;
-        
-        // Get SOL reserves (this is typically a PDA)
-        let sol_reserves = self.derive_sol_reserves(&event.bonding_curve)?;
-        
-        // Calculate min tokens out (simplified - should use bonding curve formula)
-        let min_tokens_out = 0; // Calculate based on bonding curve
+        let quote = self.quote_buy(event, evaluated_curve, buy_amount_lamports).await?;
         
+        // Create the buyer's token account in the same transaction; a no-op
+        // when it already exists, so no existence check is needed
+        let create_ata_ix = create_associated_token_account_idempotent(
+            &self.wallet.pubkey(),
+            &self.wallet.pubkey(),
+            &event.mint,
+            &event.token_program,
+        );
+
         let buy_ix = build_buy_instruction(
             &self.wallet.pubkey(),
-            &event.bonding_curve,
-            &associated_bonding_curve,
             &event.mint,
-            &sol_reserves,
-            &event.mint, // Token mint
-            buy_amount_lamports,
-            min_tokens_out,
+            &quote.creator,
+            &quote.fee_recipient,
+            &event.token_program,
+            quote.token_amount,
+            quote.max_sol_cost,
         )?;
 
-        // Build transaction
-        let mut transaction = solana_sdk::transaction::Transaction::new_with_payer(
-            &[buy_ix],
-            Some(&self.wallet.pubkey()),
+        // Add priority fee
+        let priority_fee = self.priority_fees.estimate();
+
+        // Build and sign the transaction once every instruction is in place
+        let mut builder = TransactionBuilder::new(
+            self.wallet.pubkey(),
+            self.config.max_compute_units,
+            priority_fee,
         );
-        transaction.sign(&[self.wallet.keypair()], blockhash);
+        builder.push(create_ata_ix).push(buy_ix);
+
+        let signature = self.submit(builder).await?;
+
+        match fill::fetch_buy_fill(&self.rpc_client, &signature, &self.wallet.pubkey(), &event.mint).await {
+            Ok(fill) => {
+                log::info!(
+                    "Bought {} tokens of {} for {:.6} SOL (+{:.6} SOL fees) in slot {}",
+                    fill.tokens,
+                    event.mint,
+                    utils::lamports_to_sol(fill.sol_spent),
+                    utils::lamports_to_sol(fill.fees),
+                    fill.slot
+                );
+                Ok(fill)
+            }
+            Err(e) => {
+                log::warn!("Failed to read fill of {}, estimating it from the quote: {:#}", signature, e);
+                Ok(BuyFill::estimate(signature, quote.token_amount, quote.quoted_sol))
+            }
+        }
+    }
 
-        // Add priority fee
-        let priority_fee = utils::estimate_priority_fee(
-            &self.rpc_client,
-            self.config.priority_fee_micro_lamports,
-        )
-        .await;
-
-        // Convert to VersionedTransaction if needed
-        // For now, use regular transaction
-        let versioned_tx = VersionedTransaction::from(transaction);
-
-        // Send with retry
-        self.send_transaction_with_retry(versioned_tx, 3).await
+    /// Read the fill of a confirmed buy of `mint` from this wallet
+    pub async fn fetch_buy_fill(&self, signature: &str, mint: &Pubkey) -> Result<BuyFill> {
+        fill::fetch_buy_fill(&self.rpc_client, signature, &self.wallet.pubkey(), mint).await
+    }
+
+    /// Sell tokens of `mint` back to its bonding curve
+    ///
+    /// `min_sol_output` is the current curve's quote for the amount, less
+    /// `slippage_bps`. Once the curve has completed, the sell goes to the
+    /// token's PumpSwap pool instead.
+    pub async fn execute_sell(&self, mint: &Pubkey, amount: SellAmount) -> Result<String, SnipeError> {
+        let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
+            .context("Failed to parse Pump.fun program ID")?;
+
+        let token_program = self
+            .rpc_client
+            .get_account(mint)
+            .await
+            .with_context(|| format!("Failed to fetch mint {}", mint))?
+            .owner;
+
+        let token_amount = self.sell_token_amount(mint, &token_program, amount).await?;
+        if token_amount == 0 {
+            return Err(anyhow::anyhow!("Nothing to sell for {}", mint).into());
+        }
+
+        let global = self.global().await?;
+        let fee_config = self.fee_config().await?;
+        let curve = BondingCurve::fetch(&self.rpc_client, &pda::bonding_curve(&program_id, mint)).await?;
+        if curve.complete {
+            log::info!("Bonding curve for {} is complete; selling on PumpSwap", mint);
+            return self.execute_amm_sell(mint, &token_program, token_amount).await;
+        }
+
+        let quote = curve.quote_sell(global, Some(fee_config), token_amount);
+        let min_sol_output = utils::sub_slippage(quote.net_sol, self.config.slippage_bps);
+
+        log::info!(
+            "Selling {} tokens of {}: quote {:.6} SOL, min {:.6} SOL",
+            token_amount,
+            mint,
+            utils::lamports_to_sol(quote.net_sol),
+            utils::lamports_to_sol(min_sol_output)
+        );
+
+        if self.config.dry_run {
+            log::info!("[DRY RUN] Would sell {} tokens of {}", token_amount, mint);
+            return Ok("dry_run_simulation".to_string());
+        }
+
+        let sell_ix = build_sell_instruction(
+            &self.wallet.pubkey(),
+            mint,
+            &curve.creator,
+            &global.fee_recipient,
+            &token_program,
+            token_amount,
+            min_sol_output,
+        )?;
+
+        let mut builder = TransactionBuilder::new(
+            self.wallet.pubkey(),
+            self.config.max_compute_units,
+            self.priority_fees.estimate(),
+        );
+        builder.push(sell_ix);
+
+        self.submit(builder).await
     }
 
-    /// Derive associated bonding curve address
-    fn derive_associated_bonding_curve(&self, bonding_curve: &Pubkey) -> Result<Pubkey> {
-        // This is a placeholder - actual derivation depends on Pump.fun program
-        // You may need to use findProgramAddress or similar
-        Ok(*bonding_curve) // Simplified
+    /// Sell tokens of a migrated `mint` into its canonical PumpSwap pool
+    ///
+    /// Proceeds arrive as wrapped SOL, so the wallet's wrapped SOL account is
+    /// created beforehand and closed afterwards to unwrap them.
+    async fn execute_amm_sell(
+        &self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        token_amount: u64,
+    ) -> Result<String, SnipeError> {
+        let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID)
+            .context("Failed to parse Pump.fun program ID")?;
+        let amm_program_id = Pubkey::from_str(PUMP_AMM_PROGRAM_ID)
+            .context("Failed to parse PumpSwap program ID")?;
+        let wsol = spl_token::native_mint::ID;
+
+        let pool_address = pda::canonical_pool(&amm_program_id, &program_id, mint, &wsol);
+        let pool = pump_amm::Pool::fetch(&self.rpc_client, &pool_address)
+            .await
+            .with_context(|| format!("{} has not migrated to PumpSwap yet", mint))?;
+        let global_config =
+            pump_amm::GlobalConfig::fetch(&self.rpc_client, &pda::amm_global_config(&amm_program_id)).await?;
+        let protocol_fee_recipient = global_config
+            .protocol_fee_recipient()
+            .context("PumpSwap has no protocol fee recipient configured")?;
+
+        let (base_reserve, quote_reserve) = pool.fetch_reserves(&self.rpc_client).await?;
+        let quote = pool.quote_sell(&global_config, base_reserve, quote_reserve, token_amount);
+        let min_quote_out = utils::sub_slippage(quote.net_quote, self.config.slippage_bps);
+
+        log::info!(
+            "Selling {} tokens of {} on PumpSwap pool {}: quote {:.6} SOL, min {:.6} SOL",
+            token_amount,
+            mint,
+            pool_address,
+            utils::lamports_to_sol(quote.net_quote),
+            utils::lamports_to_sol(min_quote_out)
+        );
+
+        if self.config.dry_run {
+            log::info!("[DRY RUN] Would sell {} tokens of {} on PumpSwap", token_amount, mint);
+            return Ok("dry_run_simulation".to_string());
+        }
+
+        let user = self.wallet.pubkey();
+        let wsol_account = get_associated_token_address_with_program_id(&user, &wsol, &spl_token::ID);
+
+        let sell_ix = build_amm_sell_instruction(
+            &user,
+            &pool_address,
+            &pool,
+            &protocol_fee_recipient,
+            token_program,
+            token_amount,
+            min_quote_out,
+        )?;
+        let unwrap_ix = spl_token::instruction::close_account(&spl_token::ID, &wsol_account, &user, &user, &[])
+            .context("Failed to build wrapped SOL close instruction")?;
+
+        let mut builder = TransactionBuilder::new(
+            user,
+            self.config.max_compute_units,
+            self.priority_fees.estimate(),
+        );
+        builder
+            .push(create_associated_token_account_idempotent(&user, &user, &wsol, &spl_token::ID))
+            .push(sell_ix)
+            .push(unwrap_ix);
+
+        self.submit(builder).await
     }
 
-    /// Derive SOL reserves PDA
-    fn derive_sol_reserves(&self, bonding_curve: &Pubkey) -> Result<Pubkey> {
-        // Placeholder - derive actual PDA
-        Ok(*bonding_curve) // Simplified
+    /// Resolve a sell request into raw token units, checked against the balance
+    async fn sell_token_amount(
+        &self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        amount: SellAmount,
+    ) -> Result<u64> {
+        let token_account = get_associated_token_address_with_program_id(
+            &self.wallet.pubkey(),
+            mint,
+            token_program,
+        );
+        let balance = self
+            .rpc_client
+            .get_token_account_balance(&token_account)
+            .await
+            .with_context(|| format!("No token account for {} in this wallet", mint))?;
+        let held = u64::from_str(&balance.amount).context("Invalid token account balance")?;
+
+        let requested = match amount {
+            SellAmount::Units(units) => units,
+            SellAmount::Tokens(tokens) => (tokens * 10f64.powi(balance.decimals as i32)) as u64,
+            SellAmount::Percent(percent) => {
+                if !(percent > 0.0 && percent <= 100.0) {
+                    anyhow::bail!("Sell percentage must be in (0, 100], got {}", percent);
+                }
+                (held as u128 * (percent * 100.0).round() as u128 / 10_000) as u64
+            }
+        };
+
+        if requested > held {
+            anyhow::bail!("Cannot sell {} tokens of {}, wallet holds {}", requested, mint, held);
+        }
+
+        Ok(requested)
     }
 
-    /// Get or create associated token account
-    async fn get_or_create_token_account(&self, mint: &Pubkey) -> Result<Pubkey> {
-        // Derive ATA address using SPL library
-        use spl_associated_token_account::get_associated_token_address;
-        use spl_token::ID as TOKEN_PROGRAM_ID;
-        
-        let ata = get_associated_token_address(&self.wallet.pubkey(), mint);
-        
-        // Check if account exists
-        match self.rpc_client.get_account_data(&ata).await {
-            Ok(_) => Ok(ata),
-            Err(_) => {
-                // Account doesn't exist - would need to create it
-                // For now, return the address (creation instruction would be added to transaction)
-                Ok(ata)
+    /// Sign and broadcast a transaction
+    ///
+    /// With Jito enabled the tip is part of the transaction itself, so the
+    /// bundle and the RPC sends carry the same signature. With simulation
+    /// enabled the transaction is simulated first and not sent if it would
+    /// fail.
+    async fn submit(&self, mut builder: TransactionBuilder) -> Result<String, SnipeError> {
+        let blockhash = self.blockhashes.get(&self.rpc_client).await?;
+
+        if let Some(tip) = self.broadcaster.tip_instruction(&self.wallet.pubkey()) {
+            builder.push(tip);
+        }
+
+        if self.config.simulate_transactions {
+            self.simulate(&mut builder, blockhash.hash).await?;
+        }
+
+        self.send_transaction_with_retry(builder, blockhash, MAX_SEND_ATTEMPTS).await
+    }
+
+    /// Simulate a transaction and fit its compute unit limit to the result
+    ///
+    /// The limit becomes the units consumed plus
+    /// `compute_unit_margin_percent`, capped at `max_compute_units`.
+    async fn simulate(&self, builder: &mut TransactionBuilder, blockhash: Hash) -> Result<(), SnipeError> {
+        let transaction = builder.sign(self.wallet.keypair(), blockhash)?;
+        let result = self
+            .rpc_client
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .value;
+
+        let logs = result.logs.unwrap_or_default();
+        if let Some(error) = result.err {
+            for line in &logs {
+                log::debug!("  {}", line);
             }
+            return Err(SnipeError::from_transaction_error(
+                error,
+                &builder.instructions(),
+                |error| SnipeError::SimulationFailed { error, logs },
+            ));
         }
+
+        if let Some(units) = result.units_consumed {
+            let limit = (units + units * self.config.compute_unit_margin_percent / 100)
+                .min(self.config.max_compute_units as u64) as u32;
+            log::info!("Simulation consumed {} compute units; limit set to {}", units, limit);
+            builder.set_compute_unit_limit(limit);
+        }
+
+        Ok(())
     }
 
-    /// Send transaction with retry logic
+    /// Send a transaction until it lands
+    ///
+    /// A signed transaction is resent as is for as long as its blockhash is
+    /// valid, since a send that failed may still have reached the network.
+    /// Only once that blockhash has expired is the retry re-signed, with the
+    /// latest cached blockhash and a priority fee raised by
+    /// `retry_fee_bump_percent`, so two signatures can never both land. A
+    /// preflight failure raised by Pump.fun is returned straight away, as
+    /// resending cannot fix it. Giving up while the last transaction's
+    /// blockhash may still be valid returns [`SnipeError::Unconfirmed`].
     async fn send_transaction_with_retry(
         &self,
-        transaction: VersionedTransaction,
-        max_retries: u32,
-    ) -> Result<String> {
+        mut builder: TransactionBuilder,
+        mut blockhash: RecentBlockhash,
+        max_attempts: u32,
+    ) -> Result<String, SnipeError> {
+        let instructions = builder.instructions();
+        let mut transaction = builder.sign(self.wallet.keypair(), blockhash.hash)?;
         let mut last_error = None;
+        // Whether the latest transaction's fate is unknown after a failed send
+        let mut unsettled = false;
+        // Whether any transaction may have reached the network
+        let mut sent = false;
+
+        for attempt in 1..=max_attempts {
+            if attempt > 1 {
+                if self.blockhash_expired(&blockhash).await {
+                    blockhash = self.blockhashes.get(&self.rpc_client).await?;
+                    let fee = self.bumped_fee(builder.compute_unit_price());
+                    builder.set_compute_unit_price(fee);
+                    log::info!(
+                        "Re-signing with blockhash {} and priority fee {} micro-lamports",
+                        blockhash.hash,
+                        fee
+                    );
+                    transaction = builder.sign(self.wallet.keypair(), blockhash.hash)?;
+                } else {
+                    log::info!("Resending {} while its blockhash is still valid", transaction.signatures[0]);
+                }
+            }
 
-        for attempt in 1..=max_retries {
-            log::info!("Sending buy transaction (attempt {}/{})", attempt, max_retries);
-
-            match self.rpc_client.send_transaction(&transaction).await {
-                Ok(signature) => {
-                    log::info!("Buy transaction sent: {}", signature);
-                    
-                    // Wait for confirmation
-                    if let Err(e) = self.wait_for_confirmation(&signature).await {
-                        log::warn!("Transaction sent but confirmation error: {}", e);
+            log::info!("Sending transaction (attempt {}/{})", attempt, max_attempts);
+
+            let result = self.broadcaster.send(&transaction).await;
+            sent = true;
+            match result {
+                Ok(broadcast) => {
+                    unsettled = false;
+                    let signature = broadcast.signature;
+                    log::info!("Transaction sent: {}", signature);
+
+                    let outcome = self
+                        .confirmer
+                        .wait(&self.rpc_client, &signature, blockhash.last_valid_block_height)
+                        .await;
+                    if let TxOutcome::Confirmed { slot } | TxOutcome::LandedFailed { slot, .. } = outcome {
+                        self.broadcaster.record_landing(&broadcast, slot);
+                    }
+                    match settle(outcome, &signature, &instructions) {
+                        Some(result) => return result,
+                        None => last_error = Some(anyhow::anyhow!("Transaction {} expired", signature).into()),
                     }
-
-                    return Ok(signature.to_string());
                 }
                 Err(e) => {
                     log::warn!("Transaction send failed (attempt {}): {}", attempt, e);
-                    last_error = Some(e);
 
-                    if attempt < max_retries {
+                    let error = match e.get_transaction_error() {
+                        Some(error) => SnipeError::from_transaction_error(error, &instructions, |_| e.into()),
+                        None => e.into(),
+                    };
+                    if error.pump_error().is_some() {
+                        return Err(error);
+                    }
+                    last_error = Some(error);
+                    unsettled = true;
+
+                    if attempt < max_attempts {
                         let delay = Duration::from_millis(1000 * attempt as u64);
                         log::info!("Retrying in {:?}...", delay);
                         sleep(delay).await;
@@ -231,45 +717,57 @@ impl Sniper {
             }
         }
 
+        // A failed send may still have reached the network; only its final
+        // outcome tells whether it landed
+        if unsettled {
+            let signature = transaction.signatures[0];
+            log::info!("Waiting to see whether {} landed despite the failed send", signature);
+
+            let outcome = self
+                .confirmer
+                .wait(&self.rpc_client, &signature, blockhash.last_valid_block_height)
+                .await;
+            if let Some(result) = settle(outcome, &signature, &instructions) {
+                return result;
+            }
+        }
+
+        // Earlier signatures were only replaced once their blockhash expired,
+        // so the last one is the only one that could still land
+        if sent && !self.blockhash_expired(&blockhash).await {
+            return Err(SnipeError::Unconfirmed {
+                signature: transaction.signatures[0].to_string(),
+            });
+        }
+
         Err(last_error
-            .unwrap_or_else(|| anyhow::anyhow!("Transaction failed after {} retries", max_retries))
-            .into())
+            .unwrap_or_else(|| anyhow::anyhow!("Transaction failed after {} attempts", max_attempts).into()))
     }
 
-    /// Wait for transaction confirmation
-    async fn wait_for_confirmation(
-        &self,
-        signature: &solana_sdk::signature::Signature,
-    ) -> Result<()> {
-        const MAX_WAIT_TIME: Duration = Duration::from_secs(30);
-        const POLL_INTERVAL: Duration = Duration::from_millis(500);
-        let start = std::time::Instant::now();
-
-        loop {
-            if start.elapsed() > MAX_WAIT_TIME {
-                anyhow::bail!("Transaction confirmation timeout");
+    /// Whether a blockhash is past its last valid block height
+    ///
+    /// An unknown block height counts as not expired.
+    async fn blockhash_expired(&self, blockhash: &RecentBlockhash) -> bool {
+        match self.rpc_client.get_block_height().await {
+            Ok(height) => height > blockhash.last_valid_block_height,
+            Err(e) => {
+                log::debug!("Failed to get block height: {}", e);
+                false
             }
+        }
+    }
 
-            match self.rpc_client.get_signature_status(signature).await {
-                Ok(Some(status)) => {
-                    if status.err.is_some() {
-                        anyhow::bail!("Transaction failed: {:?}", status.err);
-                    }
-                    if status.confirmation_status.is_some() {
-                        log::info!("Transaction confirmed: {}", signature);
-                        return Ok(());
-                    }
-                }
-                Ok(None) => {
-                    // Still processing
-                }
-                Err(e) => {
-                    log::warn!("Error checking transaction status: {}", e);
-                }
-            }
+    /// Priority fee for a retry, raised by `retry_fee_bump_percent` up to the cap
+    fn bumped_fee(&self, fee: u64) -> u64 {
+        let bumped = fee.saturating_add(fee * self.config.retry_fee_bump_percent / 100);
+        bumped
+            .max(fee + 1)
+            .min(self.config.priority_fee_cap_micro_lamports.max(fee))
+    }
 
-            sleep(POLL_INTERVAL).await;
-        }
+    /// Transaction broadcaster, for its per-endpoint statistics
+    pub fn broadcaster(&self) -> &Broadcaster {
+        &self.broadcaster
     }
 
     /// Get wallet balance
@@ -283,3 +781,35 @@ impl Sniper {
         Ok(balance)
     }
 }
+
+/// Result of a sent transaction that reached a final outcome, or `None` if
+/// it expired without landing
+fn settle(
+    outcome: TxOutcome,
+    signature: &Signature,
+    instructions: &[Instruction],
+) -> Option<Result<String, SnipeError>> {
+    match outcome {
+        TxOutcome::Confirmed { slot } => {
+            log::info!("Transaction confirmed in slot {}: {}", slot, signature);
+            Some(Ok(signature.to_string()))
+        }
+        TxOutcome::LandedFailed { slot, error } => {
+            log::warn!("Transaction landed in slot {} but failed: {} ({})", slot, signature, error);
+            Some(Err(SnipeError::from_transaction_error(error, instructions, |error| {
+                SnipeError::TransactionFailed {
+                    signature: signature.to_string(),
+                    error: error.to_string(),
+                }
+            })))
+        }
+        TxOutcome::Expired { last_valid_block_height } => {
+            log::warn!(
+                "Transaction expired at block height {} without landing: {}",
+                last_valid_block_height,
+                signature
+            );
+            None
+        }
+    }
+}
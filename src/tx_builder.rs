@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Keypair,
+    transaction::VersionedTransaction,
+};
+
+/// Assembles instructions into a signed transaction
+///
+/// Every transaction it produces starts with SetComputeUnitLimit and
+/// SetComputeUnitPrice. Signing happens last, over the complete instruction
+/// list, and can be repeated with a new blockhash or fee.
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    payer: Pubkey,
+    compute_unit_limit: u32,
+    compute_unit_price: u64,
+    instructions: Vec<Instruction>,
+}
+
+impl TransactionBuilder {
+    pub fn new(payer: Pubkey, compute_unit_limit: u32, compute_unit_price: u64) -> Self {
+        Self {
+            payer,
+            compute_unit_limit,
+            compute_unit_price,
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Replace the compute unit limit, e.g. with a simulated figure
+    pub fn set_compute_unit_limit(&mut self, compute_unit_limit: u32) -> &mut Self {
+        self.compute_unit_limit = compute_unit_limit;
+        self
+    }
+
+    /// Current priority fee in micro-lamports per compute unit
+    pub fn compute_unit_price(&self) -> u64 {
+        self.compute_unit_price
+    }
+
+    /// Replace the priority fee, e.g. to outbid on a retry
+    pub fn set_compute_unit_price(&mut self, compute_unit_price: u64) -> &mut Self {
+        self.compute_unit_price = compute_unit_price;
+        self
+    }
+
+    /// Append an instruction after any already added
+    pub fn push(&mut self, instruction: Instruction) -> &mut Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// All instructions, compute budget first
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(self.instructions.len() + 2);
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(self.compute_unit_limit));
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(self.compute_unit_price));
+        instructions.extend(self.instructions.iter().cloned());
+        instructions
+    }
+
+    /// Compile and sign a v0 transaction
+    pub fn sign(&self, signer: &Keypair, blockhash: Hash) -> Result<VersionedTransaction> {
+        let message = v0::Message::try_compile(&self.payer, &self.instructions(), &[], blockhash)
+            .context("Failed to compile transaction message")?;
+
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[signer])
+            .context("Failed to sign transaction")
+    }
+}
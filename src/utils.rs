@@ -1,5 +1,8 @@
 use anyhow::Result;
 use log::LevelFilter;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
 
 /// Initialize logging based on log level string
 pub fn init_logging(log_level: &str) -> Result<()> {
@@ -31,24 +34,22 @@ pub fn sol_to_lamports(sol: f64) -> u64 {
     (sol * 1_000_000_000.0) as u64
 }
 
-/// Rate limiter helper - simple delay
-pub async fn rate_limit_delay(ms: u64) {
-    tokio::time::sleep(tokio::time::Duration::from_millis(ms)).await;
+/// Basis point denominator used by all fee calculations
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// `amount * bps / 10_000`, rounded up, as the Pump programs charge fees
+pub fn ceil_bps(amount: u64, bps: u64) -> u64 {
+    ((amount as u128 * bps as u128 + BPS_DENOMINATOR - 1) / BPS_DENOMINATOR) as u64
+}
+
+/// Raise an amount by a slippage tolerance in basis points
+pub fn add_slippage(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * (10_000 + slippage_bps as u128) / 10_000) as u64
 }
 
-/// Estimate priority fee dynamically
-/// 
-/// In production, you should query get_recent_prioritization_fees
-pub async fn estimate_priority_fee(
-    _rpc_client: &solana_client::nonblocking::rpc_client::RpcClient,
-    base_fee_micro_lamports: u64,
-) -> u64 {
-    // Simple heuristic - in production, query recent fees
-    // For now, return base fee with some jitter
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    let jitter = rng.gen_range(0..50_000);
-    base_fee_micro_lamports + jitter
+/// Lower an amount by a slippage tolerance in basis points
+pub fn sub_slippage(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
 }
 
 /// Check if a string looks like spam (simple heuristic)
@@ -64,3 +65,99 @@ pub fn is_spam_name(name: &str) -> bool {
 pub fn is_spam_symbol(symbol: &str) -> bool {
     symbol.len() > 10 || symbol.chars().any(|c| !c.is_alphanumeric())
 }
+
+/// Bounded set of recently seen keys
+///
+/// Once full, the oldest key is evicted to make room for the next one.
+pub struct RecentSet<T> {
+    seen: HashSet<T>,
+    order: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T: Eq + Hash + Clone> RecentSet<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record a key, returning false if it was already present
+    pub fn insert(&mut self, key: T) -> bool {
+        if !self.seen.insert(key.clone()) {
+            return false;
+        }
+
+        self.order.push_back(key);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+
+        true
+    }
+}
+
+/// Minimal reader for Borsh-encoded Anchor instruction, event and account data
+pub struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// True once every byte has been consumed
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        match self.read_u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read_bytes(4)?.try_into().ok()?))
+    }
+
+    pub fn read_u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.read_bytes(8)?.try_into().ok()?))
+    }
+
+    pub fn read_u128(&mut self) -> Option<u128> {
+        Some(u128::from_le_bytes(self.read_bytes(16)?.try_into().ok()?))
+    }
+
+    pub fn read_i64(&mut self) -> Option<i64> {
+        Some(i64::from_le_bytes(self.read_bytes(8)?.try_into().ok()?))
+    }
+
+    pub fn read_pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.read_bytes(32)?).ok()
+    }
+
+    pub fn read_string(&mut self) -> Option<String> {
+        let len = self.read_u32()? as usize;
+        String::from_utf8(self.read_bytes(len)?.to_vec()).ok()
+    }
+}